@@ -0,0 +1,49 @@
+param(
+    [string]$ListsDir = 'assets\lists',
+    [string]$IndexPath = 'assets\index.json'
+)
+
+# Rebuilds the list index from the files in $ListsDir. Titles, categories,
+# descriptions, thumbnails and dates already in the index are kept; item
+# counts are refreshed for every list that has its own items and dropped for
+# composite lists, whose size depends on how their sources overlap.
+$ErrorActionPreference = 'Stop'
+
+$existing = @{}
+$order = @()
+$categories = @()
+if (Test-Path $IndexPath) {
+    $index = Get-Content -Path $IndexPath -Raw -Encoding UTF8 | ConvertFrom-Json
+    $entries = if ($index -is [array]) { $index } elseif ($index.lists) { $index.lists } else { @() }
+    if ($index.categories) {
+        $categories = @($index.categories)
+    }
+    foreach ($entry in $entries) {
+        if ($entry -is [string]) {
+            $entry = [pscustomobject]@{ id = $entry }
+        }
+        $existing[$entry.id] = $entry
+        $order += $entry.id
+    }
+}
+
+$files = @(Get-ChildItem -Path $ListsDir -Filter '*.json' | Sort-Object BaseName)
+$names = @($files | ForEach-Object { $_.BaseName })
+$ids = @($order | Where-Object { $names -contains $_ }) + @($names | Where-Object { $order -notcontains $_ })
+
+$lists = foreach ($id in $ids) {
+    $entry = if ($existing.ContainsKey($id)) { $existing[$id] } else { [pscustomobject]@{ id = $id } }
+    $file = $files | Where-Object { $_.BaseName -eq $id } | Select-Object -First 1
+    $content = Get-Content -Path $file.FullName -Raw -Encoding UTF8 | ConvertFrom-Json
+    $items = if ($content -is [array]) { $content } elseif ($content.items) { $content.items } else { $null }
+    if ($null -ne $items) {
+        $entry | Add-Member -NotePropertyName count -NotePropertyValue @($items).Count -Force
+    } else {
+        $entry.PSObject.Properties.Remove('count')
+    }
+    $entry
+}
+
+[pscustomobject]@{ categories = $categories; lists = @($lists) } |
+    ConvertTo-Json -Depth 5 |
+    Set-Content -Path $IndexPath -Encoding UTF8
@@ -0,0 +1,3048 @@
+﻿use crate::backup::{
+    apply_backup, decode_backup, encode_backup, is_known_backup, note_backup, Backup, ImportMode,
+};
+use crate::data::{
+    fetch_available_lists, fetch_list_text, is_custom_list_id, legacy_item_ids, load_list,
+    load_list_from_text, remote_list_id, remote_list_url, resolve_image_url, CustomList, DataError,
+    ListInfo, ListItem, LoadedList,
+};
+use crate::editor::{ImportDialog, ListEditor, OpenUrlForm};
+use crate::export::{export_file_name, format_ranking, ranked_indices, ranking_rows, ExportFormat};
+use crate::filter::{list_tags, ItemFilter};
+use crate::history::{new_session_id, EventStamp, MatchEvent};
+use crate::matchflow::{Matchup, MatchupContext, MatchupMode, MatchupStrategy, TopKState};
+use crate::ranking::{RatingEngine, RatingEngineKind};
+use crate::storage::{
+    align_list_state, cache_list, load_list_state, load_state as load_storage_state,
+    remove_cached_list, remove_custom_list, save_state as persist_state, share_history_edit,
+    share_latest_result, upsert_custom_list, upsert_list_state, CachedList, StoredListState,
+};
+use gloo_timers::callback::Timeout;
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+use std::collections::HashSet;
+use std::ops::Deref;
+use wasm_bindgen::prelude::wasm_bindgen;
+use wasm_bindgen::{JsCast, JsValue};
+use wasm_bindgen_futures::{spawn_local, JsFuture};
+use web_sys::{
+    window, Blob, BlobPropertyBag, Element, File, HtmlAnchorElement, HtmlImageElement,
+    HtmlInputElement, Url,
+};
+use yew::prelude::*;
+
+const SWIPE_THRESHOLD: f64 = 80.0;
+const FLASH_CLEAR_DELAY_MS: u32 = 1000;
+const MATCH_RESOLVE_DELAY_MS: u32 = 260;
+const ENTER_ANIMATION_DURATION_MS: u32 = 1200;
+const ENTER_ANIMATION_BUFFER_MS: u32 = 80;
+const MATCHUP_HISTORY_LEN: usize = 8;
+const MAX_TAG_SUGGESTIONS: usize = 16;
+const MAX_HISTORY_ROWS: usize = 50;
+const DOWNLOAD_REVOKE_DELAY_MS: u32 = 1000;
+
+/// A change to the selected list's decision log.
+#[derive(Clone, Copy, PartialEq)]
+enum HistoryAction {
+    Undo,
+    Redo,
+    /// Drop the decision at this index in `events`.
+    Revert(usize),
+    /// Give the decision at this index to the other item.
+    Flip(usize),
+}
+
+#[derive(Clone, PartialEq)]
+struct DragState {
+    pointer_id: i32,
+    start_x: f64,
+    start_y: f64,
+    current_x: f64,
+    current_y: f64,
+}
+
+impl DragState {
+    fn delta(&self) -> (f64, f64) {
+        (self.current_x - self.start_x, self.current_y - self.start_y)
+    }
+}
+
+struct SwipeBackground {
+    start_rgb: (u8, u8, u8),
+    end_rgb: (u8, u8, u8),
+    start_alpha: f64,
+    end_alpha: f64,
+}
+
+impl SwipeBackground {
+    fn start_color_value(&self) -> String {
+        let (r, g, b) = self.start_rgb;
+        format!("{r} {g} {b}")
+    }
+
+    fn end_color_value(&self) -> String {
+        let (r, g, b) = self.end_rgb;
+        format!("{r} {g} {b}")
+    }
+
+    fn start_alpha_value(&self) -> String {
+        format!("{:.4}", self.start_alpha.clamp(0.0, 1.0))
+    }
+
+    fn end_alpha_value(&self) -> String {
+        format!("{:.4}", self.end_alpha.clamp(0.0, 1.0))
+    }
+}
+
+#[derive(Clone)]
+struct PointerCallbacks {
+    down: Callback<web_sys::PointerEvent>,
+    move_cb: Callback<web_sys::PointerEvent>,
+    up: Callback<web_sys::PointerEvent>,
+    cancel: Callback<web_sys::PointerEvent>,
+}
+
+impl PointerCallbacks {
+    fn noop() -> Self {
+        let noop = Callback::from(|_: web_sys::PointerEvent| {});
+        Self {
+            down: noop.clone(),
+            move_cb: noop.clone(),
+            up: noop.clone(),
+            cancel: noop,
+        }
+    }
+}
+
+#[derive(PartialEq, Clone)]
+enum FetchStatus {
+    Idle,
+    Loading,
+    Error(String),
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum WinnerSide {
+    Left,
+    Right,
+    Tie,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum CardTransition {
+    Idle,
+    Exiting { side: WinnerSide, offset: f64 },
+    Entering { side: WinnerSide },
+}
+
+#[function_component(App)]
+fn app() -> Html {
+    let list_status = use_state(|| FetchStatus::Loading);
+    let lists = use_state(|| None::<Vec<ListInfo>>);
+    let startup = use_memo(|_| load_storage_state(), ());
+    let persisted_state = {
+        let startup = startup.clone();
+        use_state(move || startup.0.clone())
+    };
+    // Shown until dismissed when the saved state had to be set aside.
+    let storage_notice = {
+        let startup = startup.clone();
+        use_state(move || startup.1.clone())
+    };
+
+    let initial_selection = persisted_state.selected_list.clone();
+    let selected_list = use_state(move || shared_list_from_location().or(initial_selection));
+
+    let items_status = use_state(|| FetchStatus::Idle);
+    // Shown above the cards when a shared list is opened from its saved copy.
+    let list_notice = use_state(|| None::<String>);
+    let loaded_list = use_state(|| None::<LoadedList>);
+    let ranking_state = use_state(|| None::<Box<dyn RatingEngine>>);
+    let current_match = use_state(|| None::<Matchup>);
+    let recent_matchups = use_state(Vec::<Matchup>::new);
+    let matchup_rng = use_mut_ref(StdRng::from_entropy);
+    let session_id = use_memo(|_| new_session_id(&mut StdRng::from_entropy()), ());
+    let failed_images = use_mut_ref(HashSet::<String>::new);
+    let force_update = use_force_update();
+    let list_state = use_state(|| None::<StoredListState>);
+    let drag_state = use_state(|| None::<DragState>);
+    let card_transition = use_state(|| CardTransition::Idle);
+    let flash_side = use_state(|| None::<WinnerSide>);
+    let menu_open = use_state(|| false);
+    let lists_expanded = use_state(|| false);
+    let history_expanded = use_state(|| false);
+    let list_search = use_state(String::new);
+    let show_reset_confirm = use_state(|| false);
+    // The custom list being edited, and whether it is new.
+    let editor_draft = use_state(|| None::<(CustomList, bool)>);
+    // Bumped to reload the selected list after its definition changed.
+    let list_revision = use_state(|| 0u32);
+    // Name and contents of a file waiting for its columns to be chosen.
+    let import_draft = use_state(|| None::<(String, String)>);
+    // Name and contents of a backup waiting to be merged or to replace.
+    let backup_draft = use_state(|| None::<(String, Backup)>);
+
+    {
+        let list_status = list_status.clone();
+        let lists = lists.clone();
+        let selected_list = selected_list.clone();
+        let persisted_state = persisted_state.clone();
+
+        use_effect_with_deps(
+            move |_| {
+                list_status.set(FetchStatus::Loading);
+
+                let list_status = list_status.clone();
+                let lists = lists.clone();
+                let selected_list = selected_list.clone();
+                let previously_selected = (*selected_list).clone();
+                let persisted_state = persisted_state.clone();
+
+                spawn_local(async move {
+                    match fetch_available_lists().await {
+                        Ok(fetched) => {
+                            let previous = previously_selected
+                                .or_else(|| persisted_state.selected_list.clone());
+                            let mut available = fetched.clone();
+                            available
+                                .extend(persisted_state.custom_lists.iter().map(CustomList::info));
+                            available
+                                .extend(persisted_state.cached_lists.iter().map(CachedList::info));
+                            let default_selection = match previous {
+                                // A shared link is opened even before it was ever cached.
+                                Some(id) if remote_list_url(&id).is_some() => Some(id),
+                                previous => resolve_selection(&available, previous),
+                            };
+                            lists.set(Some(fetched));
+                            if let Some(selection) = default_selection {
+                                selected_list.set(Some(selection));
+                            }
+                            list_status.set(FetchStatus::Idle);
+                        }
+                        Err(err) => {
+                            list_status.set(FetchStatus::Error(err.to_string()));
+                            lists.set(None);
+                            // Lists made in the app or opened from a URL do not need the index.
+                            let previous = previously_selected
+                                .or_else(|| persisted_state.selected_list.clone())
+                                .filter(|id| {
+                                    remote_list_url(id).is_some()
+                                        || persisted_state
+                                            .custom_lists
+                                            .iter()
+                                            .any(|list| &list.id == id)
+                                });
+                            selected_list.set(previous);
+                        }
+                    }
+                });
+
+                || ()
+            },
+            (),
+        );
+    }
+
+    {
+        let selected_list = selected_list.clone();
+        let items_status = items_status.clone();
+        let list_notice = list_notice.clone();
+        let loaded_list = loaded_list.clone();
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let list_state_handle = list_state.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let drag_state_handle = drag_state.clone();
+
+        use_effect_with_deps(
+            move |(selected, _revision): &(Option<String>, u32)| {
+                match selected {
+                    Some(id) => {
+                        items_status.set(FetchStatus::Loading);
+                        list_notice.set(None);
+                        loaded_list.set(None);
+                        ranking_state.set(None);
+                        current_match.set(None);
+                        recent_matchups.set(Vec::new());
+                        list_state_handle.set(None);
+                        drag_state_handle.set(None);
+
+                        let id = id.clone();
+                        let items_status = items_status.clone();
+                        let list_notice = list_notice.clone();
+                        let loaded_list = loaded_list.clone();
+                        let ranking_state = ranking_state.clone();
+                        let current_match = current_match.clone();
+                        let matchup_rng = matchup_rng.clone();
+                        let list_state_handle = list_state_handle.clone();
+                        let persisted_state_handle = persisted_state_handle.clone();
+                        let persisted_snapshot = (*persisted_state_handle).clone();
+                        let drag_state_handle = drag_state_handle.clone();
+                        let custom = persisted_snapshot
+                            .custom_lists
+                            .iter()
+                            .find(|list| list.id == id)
+                            .cloned();
+                        let cached = persisted_snapshot
+                            .cached_lists
+                            .iter()
+                            .find(|list| list.id == id)
+                            .cloned();
+
+                        spawn_local(async move {
+                            let mut fetched_text = None;
+                            let mut notice = None;
+                            let loaded = match custom {
+                                Some(custom) => custom.to_loaded(),
+                                None if is_custom_list_id(&id) => {
+                                    Err(DataError::NotFound(id.clone()))
+                                }
+                                None if remote_list_url(&id).is_some() => {
+                                    match fetch_list_text(&id).await {
+                                        Ok(text) => {
+                                            let list = load_list_from_text(&id, text.clone()).await;
+                                            if list.is_ok() {
+                                                fetched_text = Some(text);
+                                            }
+                                            list
+                                        }
+                                        Err(err) => match cached {
+                                            Some(cached) => {
+                                                notice = Some(format!(
+                                                    "{} Showing the copy saved earlier.",
+                                                    err
+                                                ));
+                                                load_list_from_text(&id, cached.text).await
+                                            }
+                                            None => Err(err),
+                                        },
+                                    }
+                                }
+                                None => load_list(&id).await,
+                            };
+                            list_notice.set(notice);
+                            match loaded {
+                                Ok(list) => {
+                                    let item_ids: Vec<String> =
+                                        list.items.iter().map(|item| item.id.clone()).collect();
+
+                                    let existing =
+                                        load_list_state(&persisted_snapshot, &id).cloned();
+                                    let legacy_ids = legacy_item_ids(&list);
+                                    let mut stored_state =
+                                        align_list_state(existing, &item_ids, &legacy_ids);
+                                    stored_state.ensure_mode_state();
+                                    // Comparisons made in the lists this one is built from.
+                                    for source in &list.sources {
+                                        if let Some(source_state) =
+                                            load_list_state(&persisted_snapshot, source)
+                                        {
+                                            stored_state.inherit_results(source, source_state);
+                                        }
+                                    }
+
+                                    let ranking = stored_state.restore_engine();
+                                    stored_state.store_engine(ranking.as_ref());
+                                    stored_state.refresh_eliminations(ranking.as_ref());
+
+                                    let mut updated_app_state = persisted_snapshot.clone();
+                                    if let Some(text) = fetched_text {
+                                        cache_list(
+                                            &mut updated_app_state,
+                                            CachedList {
+                                                id: id.clone(),
+                                                title: list.info.label.clone(),
+                                                text,
+                                            },
+                                        );
+                                    }
+                                    upsert_list_state(
+                                        &mut updated_app_state,
+                                        &id,
+                                        stored_state.clone(),
+                                    );
+                                    persist_state(&updated_app_state);
+                                    persisted_state_handle.set(updated_app_state);
+
+                                    let next_match = select_matchup(
+                                        &stored_state,
+                                        &list.items,
+                                        ranking.as_ref(),
+                                        &[],
+                                        &mut *matchup_rng.borrow_mut(),
+                                    );
+
+                                    list_state_handle.set(Some(stored_state));
+                                    ranking_state.set(Some(ranking));
+                                    current_match.set(next_match);
+                                    loaded_list.set(Some(list));
+                                    drag_state_handle.set(None);
+                                    items_status.set(FetchStatus::Idle);
+                                }
+                                Err(err) => {
+                                    items_status.set(FetchStatus::Error(err.to_string()));
+                                    loaded_list.set(None);
+                                    ranking_state.set(None);
+                                    current_match.set(None);
+                                    list_state_handle.set(None);
+                                    drag_state_handle.set(None);
+                                }
+                            }
+                        });
+                    }
+                    None => {
+                        loaded_list.set(None);
+                        ranking_state.set(None);
+                        current_match.set(None);
+                        list_state_handle.set(None);
+                        drag_state_handle.set(None);
+                        items_status.set(FetchStatus::Idle);
+                    }
+                };
+
+                || ()
+            },
+            ((*selected_list).clone(), *list_revision),
+        );
+    }
+
+    {
+        let selected_list = selected_list.clone();
+        let persisted_state = persisted_state.clone();
+
+        use_effect_with_deps(
+            move |current: &Option<String>| {
+                let mut next_state = (*persisted_state).clone();
+                if next_state.selected_list != *current {
+                    next_state.selected_list = current.clone();
+                    persist_state(&next_state);
+                    persisted_state.set(next_state);
+                }
+                || ()
+            },
+            (*selected_list).clone(),
+        );
+    }
+
+    let on_match_result = {
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let loaded_list = loaded_list.clone();
+        let list_state_handle = list_state.clone();
+        let selected_list = selected_list.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let card_transition_handle = card_transition.clone();
+        let drag_state_handle = drag_state.clone();
+        let session_id = session_id.clone();
+
+        Callback::from(move |side: WinnerSide| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+
+            let Some(prev_match) = (*current_match).clone() else {
+                return;
+            };
+
+            let Some(mut ranking) = (*ranking_state).clone() else {
+                return;
+            };
+
+            let Some(list) = (*loaded_list).as_ref() else {
+                return;
+            };
+
+            let Some(mut stored_state) = (*list_state_handle).clone() else {
+                return;
+            };
+
+            let left_index = prev_match.left_index;
+            let right_index = prev_match.right_index;
+            let winner = match side {
+                WinnerSide::Left => Some(left_index),
+                WinnerSide::Right => Some(right_index),
+                WinnerSide::Tie => None,
+            };
+            let stamp = EventStamp {
+                at: js_sys::Date::now() as u64,
+                session: (*session_id).clone(),
+            };
+            if !stored_state.record_result(
+                left_index,
+                right_index,
+                winner,
+                ranking.as_mut(),
+                &stamp,
+            ) {
+                return;
+            }
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            if updated_app_state.shared_evidence {
+                share_latest_result(&mut updated_app_state, &list_id, &mut stored_state);
+            }
+
+            let mut history = (*recent_matchups).clone();
+            history.push(prev_match);
+            if history.len() > MATCHUP_HISTORY_LEN {
+                history.remove(0);
+            }
+            let next_match = select_matchup(
+                &stored_state,
+                &list.items,
+                ranking.as_ref(),
+                &history,
+                &mut *matchup_rng.borrow_mut(),
+            );
+            recent_matchups.set(history);
+
+            list_state_handle.set(Some(stored_state.clone()));
+            ranking_state.set(Some(ranking.clone()));
+
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+
+            current_match.set(next_match.clone());
+            drag_state_handle.set(None);
+
+            if next_match.is_some() {
+                card_transition_handle.set(CardTransition::Entering { side });
+                let card_transition_for_idle = card_transition_handle.clone();
+                Timeout::new(
+                    ENTER_ANIMATION_DURATION_MS + ENTER_ANIMATION_BUFFER_MS,
+                    move || {
+                        card_transition_for_idle.set(CardTransition::Idle);
+                    },
+                )
+                .forget();
+            } else {
+                card_transition_handle.set(CardTransition::Idle);
+            }
+        })
+    };
+
+    let on_reset = {
+        let selected_list = selected_list.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let list_state_handle = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let loaded_list = loaded_list.clone();
+        let items_status = items_status.clone();
+        let drag_state_handle = drag_state.clone();
+        let show_reset_confirm_handle = show_reset_confirm.clone();
+
+        Callback::from(move |_| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+
+            let Some(list) = (*loaded_list).as_ref() else {
+                return;
+            };
+
+            let item_ids: Vec<String> = list.items.iter().map(|item| item.id.clone()).collect();
+
+            let mut new_state = StoredListState::new(&item_ids);
+            if let Some(previous) = (*list_state_handle).as_ref() {
+                new_state.engine = previous.engine;
+                new_state.set_mode(previous.mode);
+                if let Some(top_k) = previous.top_k.as_ref() {
+                    new_state.top_k = Some(TopKState::new(top_k.k()));
+                }
+                new_state.filter = previous.filter.clone();
+            }
+
+            let ranking = new_state.restore_engine();
+            new_state.store_engine(ranking.as_ref());
+
+            let next_match = select_matchup(
+                &new_state,
+                &list.items,
+                ranking.as_ref(),
+                &[],
+                &mut *matchup_rng.borrow_mut(),
+            );
+            recent_matchups.set(Vec::new());
+
+            list_state_handle.set(Some(new_state.clone()));
+            ranking_state.set(Some(ranking));
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            upsert_list_state(&mut updated_app_state, &list_id, new_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+
+            items_status.set(FetchStatus::Idle);
+            current_match.set(next_match);
+            drag_state_handle.set(None);
+            show_reset_confirm_handle.set(false);
+        })
+    };
+
+    let toggle_menu_button = {
+        let menu_open = menu_open.clone();
+        let show_reset_confirm = show_reset_confirm.clone();
+        let selected_list = selected_list.clone();
+        let list_state = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let persisted_state_handle = persisted_state.clone();
+        Callback::from(move |_: yew::MouseEvent| {
+            let next = !*menu_open;
+            menu_open.set(next);
+            if !next {
+                show_reset_confirm.set(false);
+                return;
+            }
+            // Swipes only nudge the ratings; fit them properly for the
+            // rankings and the convergence report, and keep the fit.
+            let (Some(list_id), Some(mut stored_state), Some(mut ranking)) = (
+                (*selected_list).clone(),
+                (*list_state).clone(),
+                (*ranking_state).clone(),
+            ) else {
+                return;
+            };
+            ranking.converge(&stored_state.win_matrix, &stored_state.tie_matrix);
+            stored_state.store_engine(ranking.as_ref());
+            stored_state.refresh_eliminations(ranking.as_ref());
+            list_state.set(Some(stored_state.clone()));
+            ranking_state.set(Some(ranking));
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+
+    let menu_close_callback = {
+        let menu_open = menu_open.clone();
+        let show_reset_confirm = show_reset_confirm.clone();
+        Callback::from(move |_| {
+            if *menu_open {
+                menu_open.set(false);
+                show_reset_confirm.set(false);
+            }
+        })
+    };
+
+    {
+        let loaded_list = loaded_list.clone();
+        let list_state = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        use_effect_with_deps(
+            move |matchup: &Option<Matchup>| {
+                let pending = matchup.clone().and_then(|matchup| {
+                    let has_images = (*loaded_list)
+                        .as_ref()?
+                        .items
+                        .iter()
+                        .any(|item| item.image.is_some());
+                    if !has_images {
+                        return None;
+                    }
+                    // A copy makes the same draw the next swipe will, without
+                    // advancing the session's generator.
+                    let rng = matchup_rng.borrow().clone();
+                    // Wait until the current cards have settled before doing the extra work.
+                    Some(Timeout::new(ENTER_ANIMATION_DURATION_MS, move || {
+                        if let (Some(list), Some(state), Some(ranking)) = (
+                            (*loaded_list).as_ref(),
+                            (*list_state).as_ref(),
+                            (*ranking_state).as_ref(),
+                        ) {
+                            preload_next_images(
+                                list,
+                                state,
+                                ranking.as_ref(),
+                                &recent_matchups,
+                                &matchup,
+                                &rng,
+                            );
+                        }
+                    }))
+                });
+                move || drop(pending)
+            },
+            (*current_match).clone(),
+        );
+    }
+
+    let on_image_error = {
+        let failed_images = failed_images.clone();
+        Callback::from(move |url: String| {
+            if failed_images.borrow_mut().insert(url) {
+                force_update.force_update();
+            }
+        })
+    };
+
+    {
+        let drag_state = drag_state.clone();
+        use_effect_with_deps(
+            move |state: &Option<DragState>| {
+                let background = state.as_ref().and_then(|drag| {
+                    let (delta_x, delta_y) = drag.delta();
+                    swipe_background_for_delta(delta_x, delta_y)
+                });
+                if let Some(window) = window() {
+                    if let Some(document) = window.document() {
+                        if let Some(body) = document.body() {
+                            let style = body.style();
+                            let transition = if background.is_some() {
+                                "--swipe-alpha-start 0.05s ease, --swipe-alpha-end 0.05s ease"
+                            } else {
+                                "--swipe-alpha-start 1s ease, --swipe-alpha-end 1s ease"
+                            };
+                            let _ = style.set_property("transition", transition);
+                            match background {
+                                Some(bg) => {
+                                    let _ = style.set_property(
+                                        "--swipe-color-start",
+                                        &bg.start_color_value(),
+                                    );
+                                    let _ = style
+                                        .set_property("--swipe-color-end", &bg.end_color_value());
+                                    let _ = style.set_property(
+                                        "--swipe-alpha-start",
+                                        &bg.start_alpha_value(),
+                                    );
+                                    let _ = style
+                                        .set_property("--swipe-alpha-end", &bg.end_alpha_value());
+                                }
+                                None => {
+                                    let _ = style.set_property("--swipe-alpha-start", "0");
+                                    let _ = style.set_property("--swipe-alpha-end", "0");
+                                }
+                            }
+                        }
+                    }
+                }
+                || ()
+            },
+            (*drag_state).clone(),
+        );
+    }
+
+    let toggle_lists = {
+        let lists_expanded = lists_expanded.clone();
+        Callback::from(move |_| {
+            let next = !*lists_expanded;
+            lists_expanded.set(next);
+        })
+    };
+
+    let toggle_history = {
+        let history_expanded = history_expanded.clone();
+        Callback::from(move |_| history_expanded.set(!*history_expanded))
+    };
+
+    let on_search_lists = {
+        let list_search = list_search.clone();
+        Callback::from(move |query: String| list_search.set(query))
+    };
+
+    let request_reset = {
+        let show_reset_confirm = show_reset_confirm.clone();
+        Callback::from(move |_| {
+            show_reset_confirm.set(true);
+        })
+    };
+
+    let cancel_reset = {
+        let show_reset_confirm = show_reset_confirm.clone();
+        Callback::from(move |_| {
+            show_reset_confirm.set(false);
+        })
+    };
+
+    let confirm_reset = {
+        let on_reset = on_reset.clone();
+        Callback::from(move |_| {
+            on_reset.emit(());
+        })
+    };
+
+    let on_select_list = {
+        let selected_list = selected_list.clone();
+        let menu_open = menu_open.clone();
+        let show_reset_confirm = show_reset_confirm.clone();
+        Callback::from(move |list_id: String| {
+            selected_list.set(Some(list_id.clone()));
+            show_reset_confirm.set(false);
+            menu_open.set(false);
+        })
+    };
+
+    let on_new_list = {
+        let editor_draft = editor_draft.clone();
+        Callback::from(move |_| editor_draft.set(Some((CustomList::default(), true))))
+    };
+
+    let on_edit_list = {
+        let editor_draft = editor_draft.clone();
+        let persisted_state_handle = persisted_state.clone();
+        Callback::from(move |list_id: String| {
+            let custom = persisted_state_handle
+                .custom_lists
+                .iter()
+                .find(|list| list.id == list_id)
+                .cloned();
+            if let Some(custom) = custom {
+                editor_draft.set(Some((custom, false)));
+            }
+        })
+    };
+
+    let on_save_list = {
+        let editor_draft = editor_draft.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let selected_list = selected_list.clone();
+        let list_revision = list_revision.clone();
+        let menu_open = menu_open.clone();
+        Callback::from(move |mut list: CustomList| {
+            let is_new = (*editor_draft).as_ref().is_some_and(|(_, is_new)| *is_new);
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            if is_new {
+                let taken = updated_app_state
+                    .custom_lists
+                    .iter()
+                    .map(|existing| existing.id.as_str());
+                list.id = CustomList::create(&list.title, taken).id;
+            }
+            let list_id = list.id.clone();
+            upsert_custom_list(&mut updated_app_state, list);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+            editor_draft.set(None);
+
+            if (*selected_list).as_deref() == Some(list_id.as_str()) {
+                list_revision.set(*list_revision + 1);
+            } else {
+                selected_list.set(Some(list_id));
+                menu_open.set(false);
+            }
+        })
+    };
+
+    let on_import_file = {
+        let import_draft = import_draft.clone();
+        Callback::from(move |file: File| {
+            let import_draft = import_draft.clone();
+            spawn_local(async move {
+                match JsFuture::from(file.text()).await {
+                    Ok(text) => {
+                        import_draft.set(Some((file.name(), text.as_string().unwrap_or_default())))
+                    }
+                    Err(err) => log::warn!("Failed to read {}: {:?}", file.name(), err),
+                }
+            });
+        })
+    };
+
+    let on_import_list = {
+        let import_draft = import_draft.clone();
+        let editor_draft = editor_draft.clone();
+        Callback::from(move |list: CustomList| {
+            import_draft.set(None);
+            editor_draft.set(Some((list, true)));
+        })
+    };
+
+    let on_cancel_import = {
+        let import_draft = import_draft.clone();
+        Callback::from(move |_| import_draft.set(None))
+    };
+
+    let on_cancel_edit = {
+        let editor_draft = editor_draft.clone();
+        Callback::from(move |_| editor_draft.set(None))
+    };
+
+    let on_delete_list = {
+        let editor_draft = editor_draft.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let selected_list = selected_list.clone();
+        let lists = lists.clone();
+        Callback::from(move |list_id: String| {
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            remove_custom_list(&mut updated_app_state, &list_id);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+            editor_draft.set(None);
+
+            if (*selected_list).as_deref() == Some(list_id.as_str()) {
+                let fallback = (*lists)
+                    .as_ref()
+                    .and_then(|available| available.first())
+                    .map(|info| info.id.clone());
+                selected_list.set(fallback);
+            }
+        })
+    };
+
+    let on_forget_list = {
+        let persisted_state_handle = persisted_state.clone();
+        let selected_list = selected_list.clone();
+        let lists = lists.clone();
+        Callback::from(move |list_id: String| {
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            remove_cached_list(&mut updated_app_state, &list_id);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+
+            if (*selected_list).as_deref() == Some(list_id.as_str()) {
+                let fallback = (*lists)
+                    .as_ref()
+                    .and_then(|available| available.first())
+                    .map(|info| info.id.clone());
+                selected_list.set(fallback);
+            }
+        })
+    };
+
+    let on_set_shared_evidence = {
+        let persisted_state_handle = persisted_state.clone();
+        Callback::from(move |enabled: bool| {
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            updated_app_state.shared_evidence = enabled;
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+
+    let on_export_backup = {
+        let persisted_state = persisted_state.clone();
+        let storage_notice = storage_notice.clone();
+        Callback::from(move |_| {
+            let now = js_sys::Date::new_0();
+            let day: String = String::from(now.to_iso_string()).chars().take(10).collect();
+            let exported = now.get_time() as u64;
+            let result = encode_backup(&persisted_state, exported)
+                .map_err(|err| err.to_string())
+                .and_then(|text| {
+                    download_text(
+                        &format!("ranking-lists-backup-{day}.json"),
+                        "application/json",
+                        &text,
+                    )
+                    .map_err(|err| format!("{:?}", err))
+                });
+            match result {
+                Ok(()) => {
+                    let mut updated_app_state = (*persisted_state).clone();
+                    note_backup(&mut updated_app_state, exported);
+                    persist_state(&updated_app_state);
+                    persisted_state.set(updated_app_state);
+                }
+                Err(err) => {
+                    log::warn!("Failed to export backup: {}", err);
+                    storage_notice.set(Some("The backup could not be saved.".to_string()));
+                }
+            }
+        })
+    };
+
+    let on_import_backup = {
+        let backup_draft = backup_draft.clone();
+        let storage_notice = storage_notice.clone();
+        Callback::from(move |file: File| {
+            let backup_draft = backup_draft.clone();
+            let storage_notice = storage_notice.clone();
+            spawn_local(async move {
+                let text = match JsFuture::from(file.text()).await {
+                    Ok(text) => text.as_string().unwrap_or_default(),
+                    Err(err) => {
+                        log::warn!("Failed to read {}: {:?}", file.name(), err);
+                        return;
+                    }
+                };
+                match decode_backup(&text) {
+                    Ok(backup) => backup_draft.set(Some((file.name(), backup))),
+                    Err(err) => storage_notice.set(Some(format!(
+                        "{} could not be imported: {}.",
+                        file.name(),
+                        err
+                    ))),
+                }
+            });
+        })
+    };
+
+    let on_apply_backup = {
+        let backup_draft = backup_draft.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let selected_list = selected_list.clone();
+        let list_revision = list_revision.clone();
+        Callback::from(move |mode: ImportMode| {
+            let Some((_, backup)) = (*backup_draft).clone() else {
+                return;
+            };
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            if !apply_backup(&mut updated_app_state, backup, mode) {
+                backup_draft.set(None);
+                return;
+            }
+            if mode == ImportMode::Replace {
+                if let Some(id) = updated_app_state.selected_list.clone() {
+                    selected_list.set(Some(id));
+                }
+            }
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+            backup_draft.set(None);
+            // The open list picks up the imported progress.
+            list_revision.set(*list_revision + 1);
+        })
+    };
+
+    let on_export_ranking = {
+        let loaded_list = loaded_list.clone();
+        let ranking_state = ranking_state.clone();
+        let list_state = list_state.clone();
+        Callback::from(move |format: ExportFormat| {
+            let (Some(list), Some(ranking), Some(state)) = (
+                (*loaded_list).as_ref(),
+                (*ranking_state).as_ref(),
+                (*list_state).as_ref(),
+            ) else {
+                return None;
+            };
+            let rows = ranking_rows(&list.items, state, ranking.as_ref());
+            Some((
+                export_file_name(&list.info.label, format),
+                format_ranking(&rows, format),
+            ))
+        })
+    };
+
+    let on_cancel_backup = {
+        let backup_draft = backup_draft.clone();
+        Callback::from(move |_| backup_draft.set(None))
+    };
+
+    let on_retry_list = {
+        let list_revision = list_revision.clone();
+        Callback::from(move |_| list_revision.set(*list_revision + 1))
+    };
+
+    let on_select_engine = {
+        let selected_list = selected_list.clone();
+        let list_state_handle = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let persisted_state_handle = persisted_state.clone();
+        Callback::from(move |kind: RatingEngineKind| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+            let Some(mut stored_state) = (*list_state_handle).clone() else {
+                return;
+            };
+            if stored_state.engine == kind {
+                return;
+            }
+
+            stored_state.engine = kind;
+            let ranking = stored_state.restore_engine();
+            stored_state.store_engine(ranking.as_ref());
+            stored_state.refresh_eliminations(ranking.as_ref());
+
+            list_state_handle.set(Some(stored_state.clone()));
+            ranking_state.set(Some(ranking));
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+
+    let on_select_mode = {
+        let selected_list = selected_list.clone();
+        let list_state_handle = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let loaded_list = loaded_list.clone();
+        Callback::from(move |mode: MatchupMode| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+            let Some(mut stored_state) = (*list_state_handle).clone() else {
+                return;
+            };
+            let Some(ranking) = (*ranking_state).clone() else {
+                return;
+            };
+            if stored_state.mode == mode {
+                return;
+            }
+
+            stored_state.set_mode(mode);
+            stored_state.refresh_eliminations(ranking.as_ref());
+            let next_match = select_matchup(
+                &stored_state,
+                loaded_items(&loaded_list),
+                ranking.as_ref(),
+                &recent_matchups,
+                &mut *matchup_rng.borrow_mut(),
+            );
+
+            list_state_handle.set(Some(stored_state.clone()));
+            current_match.set(next_match);
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+
+    let on_set_top_k = {
+        let selected_list = selected_list.clone();
+        let list_state_handle = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let loaded_list = loaded_list.clone();
+        Callback::from(move |k: usize| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+            let Some(mut stored_state) = (*list_state_handle).clone() else {
+                return;
+            };
+            let Some(ranking) = (*ranking_state).clone() else {
+                return;
+            };
+            let max_k = stored_state.item_ids.len().saturating_sub(1).max(1);
+            let Some(top_k) = stored_state.top_k.as_mut() else {
+                return;
+            };
+            top_k.set_k(k.clamp(1, max_k));
+            stored_state.refresh_eliminations(ranking.as_ref());
+            let next_match = select_matchup(
+                &stored_state,
+                loaded_items(&loaded_list),
+                ranking.as_ref(),
+                &recent_matchups,
+                &mut *matchup_rng.borrow_mut(),
+            );
+
+            list_state_handle.set(Some(stored_state.clone()));
+            current_match.set(next_match);
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+
+    let on_set_filter = {
+        let selected_list = selected_list.clone();
+        let list_state_handle = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let loaded_list = loaded_list.clone();
+        Callback::from(move |query: String| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+            let Some(mut stored_state) = (*list_state_handle).clone() else {
+                return;
+            };
+            let Some(ranking) = (*ranking_state).clone() else {
+                return;
+            };
+            let query = query.trim().to_string();
+            if stored_state.filter == query {
+                return;
+            }
+            stored_state.filter = query;
+
+            // Keep the pair on screen when it still fits the new filter.
+            let items = loaded_items(&loaded_list);
+            let filter = ItemFilter::parse(&stored_state.filter);
+            let still_allowed = (*current_match).as_ref().is_some_and(|matchup| {
+                [matchup.left_index, matchup.right_index]
+                    .iter()
+                    .all(|&index| items.get(index).is_some_and(|item| filter.matches(item)))
+            });
+            if !still_allowed {
+                let next_match = select_matchup(
+                    &stored_state,
+                    items,
+                    ranking.as_ref(),
+                    &recent_matchups,
+                    &mut *matchup_rng.borrow_mut(),
+                );
+                current_match.set(next_match);
+            }
+
+            list_state_handle.set(Some(stored_state.clone()));
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+
+    let on_edit_history = {
+        let list_state_handle = list_state.clone();
+        let ranking_state = ranking_state.clone();
+        let current_match = current_match.clone();
+        let recent_matchups = recent_matchups.clone();
+        let matchup_rng = matchup_rng.clone();
+        let persisted_state_handle = persisted_state.clone();
+        let selected_list = selected_list.clone();
+        let loaded_list = loaded_list.clone();
+        let drag_state_handle = drag_state.clone();
+        let card_transition_handle = card_transition.clone();
+        Callback::from(move |action: HistoryAction| {
+            let Some(list_id) = (*selected_list).clone() else {
+                return;
+            };
+            let Some(mut stored_state) = (*list_state_handle).clone() else {
+                return;
+            };
+            let edit = match action {
+                HistoryAction::Undo => stored_state.undo(),
+                HistoryAction::Redo => stored_state.redo(),
+                HistoryAction::Revert(index) => stored_state.revert(index),
+                HistoryAction::Flip(index) => stored_state.flip(index),
+            };
+            let Some(edit) = edit else {
+                return;
+            };
+
+            let mut updated_app_state = (*persisted_state_handle).clone();
+            share_history_edit(&mut updated_app_state, &list_id, &mut stored_state, &edit);
+
+            // Undo shows the pair that was taken back again.
+            let position = |id: &str| stored_state.item_ids.iter().position(|item| item == id);
+            let undone = match (action, edit.removed.first()) {
+                (HistoryAction::Undo, Some(event)) => {
+                    match (position(&event.winner), position(&event.loser)) {
+                        (Some(left_index), Some(right_index)) => Some(Matchup {
+                            left_index,
+                            right_index,
+                        }),
+                        _ => None,
+                    }
+                }
+                _ => None,
+            };
+            let mut history = (*recent_matchups).clone();
+            let next_match = match undone {
+                Some(matchup) => {
+                    history.pop();
+                    Some(matchup)
+                }
+                None => select_matchup(
+                    &stored_state,
+                    loaded_items(&loaded_list),
+                    edit.engine.as_ref(),
+                    &history,
+                    &mut *matchup_rng.borrow_mut(),
+                ),
+            };
+            recent_matchups.set(history);
+
+            list_state_handle.set(Some(stored_state.clone()));
+            ranking_state.set(Some(edit.engine));
+            current_match.set(next_match);
+            drag_state_handle.set(None);
+            card_transition_handle.set(CardTransition::Idle);
+
+            upsert_list_state(&mut updated_app_state, &list_id, stored_state);
+            persist_state(&updated_app_state);
+            persisted_state_handle.set(updated_app_state);
+        })
+    };
+    let undo_available = (*list_state)
+        .as_ref()
+        .is_some_and(StoredListState::can_undo);
+    let redo_available = (*list_state)
+        .as_ref()
+        .is_some_and(|state| !state.redo.is_empty());
+    let undo_click = {
+        let on_edit_history = on_edit_history.clone();
+        Callback::from(move |_| on_edit_history.emit(HistoryAction::Undo))
+    };
+    let redo_click = {
+        let on_edit_history = on_edit_history.clone();
+        Callback::from(move |_| on_edit_history.emit(HistoryAction::Redo))
+    };
+
+    let menu_markup = render_menu(
+        &MenuView {
+            open: *menu_open,
+            lists_expanded: *lists_expanded,
+            history_expanded: *history_expanded,
+            list_search: &list_search,
+            show_reset_confirm: *show_reset_confirm,
+            status: &list_status,
+            lists: (*lists).as_deref(),
+            selected_list: (*selected_list).as_deref(),
+            loaded: (*loaded_list).as_ref(),
+            ranking: (*ranking_state).as_deref(),
+            list_state: (*list_state).as_ref(),
+            custom_lists: &persisted_state.custom_lists,
+            cached_lists: &persisted_state.cached_lists,
+            shared_evidence: persisted_state.shared_evidence,
+        },
+        &MenuCallbacks {
+            on_close: menu_close_callback.clone(),
+            on_toggle_lists: toggle_lists.clone(),
+            on_search_lists,
+            on_select_list,
+            on_new_list,
+            on_edit_list,
+            on_import_file,
+            on_forget_list,
+            on_request_reset: request_reset.clone(),
+            on_cancel_reset: cancel_reset.clone(),
+            on_confirm_reset: confirm_reset.clone(),
+            on_set_filter,
+            on_select_mode,
+            on_set_top_k,
+            on_select_engine,
+            on_set_shared_evidence,
+            on_toggle_history: toggle_history,
+            on_edit_history,
+            on_export_ranking,
+            on_export_backup,
+            on_import_backup,
+        },
+    );
+
+    let flash_overlay = match *flash_side {
+        Some(WinnerSide::Left) => html! { <div class="decision-flash left"></div> },
+        Some(WinnerSide::Right) => html! { <div class="decision-flash right"></div> },
+        Some(WinnerSide::Tie) => html! { <div class="decision-flash tie"></div> },
+        None => html! {},
+    };
+
+    let (matchup_markup, pointer_callbacks) = render_matchup_area(
+        &items_status,
+        &loaded_list,
+        &current_match,
+        &drag_state,
+        &card_transition,
+        &flash_side,
+        &on_match_result,
+        completion_message(&list_state, &ranking_state),
+        &failed_images.borrow(),
+        &on_image_error,
+    );
+
+    html! {
+        <>
+            { flash_overlay }
+            <div class="app-container"
+                onpointerdown={pointer_callbacks.down.clone()}
+                onpointermove={pointer_callbacks.move_cb.clone()}
+                onpointerup={pointer_callbacks.up.clone()}
+                onpointercancel={pointer_callbacks.cancel.clone()}>
+                <button class={classes!("hamburger-button", if *menu_open { "open" } else { "" })}
+                    data-swipe-ignore="true"
+                    onclick={toggle_menu_button.clone()}>
+                    <span></span>
+                    <span></span>
+                    <span></span>
+                </button>
+                {
+                    if !*menu_open {
+                        html! {
+                            <>
+                                <button
+                                    class={classes!("undo-button", if undo_available { None } else { Some("disabled") })}
+                                    data-swipe-ignore="true"
+                                    title="Undo"
+                                    onclick={undo_click.clone()}
+                                    disabled={!undo_available}>
+                                    { "↺" }
+                                </button>
+                                {
+                                    if redo_available {
+                                        html! {
+                                            <button class="redo-button"
+                                                data-swipe-ignore="true"
+                                                title="Redo"
+                                                onclick={redo_click.clone()}>
+                                                { "↻" }
+                                            </button>
+                                        }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </>
+                        }
+                    } else {
+                        html! {}
+                    }
+                }
+                { menu_markup }
+                {
+                    match (*editor_draft).as_ref() {
+                        Some((draft, is_new)) => html! {
+                            <ListEditor
+                                list={draft.clone()}
+                                is_new={*is_new}
+                                on_save={on_save_list}
+                                on_cancel={on_cancel_edit}
+                                on_delete={on_delete_list} />
+                        },
+                        None => html! {},
+                    }
+                }
+                {
+                    match (*import_draft).as_ref() {
+                        Some((file_name, text)) => html! {
+                            <ImportDialog
+                                file_name={file_name.clone()}
+                                text={text.clone()}
+                                on_import={on_import_list}
+                                on_cancel={on_cancel_import} />
+                        },
+                        None => html! {},
+                    }
+                }
+                {
+                    match (*backup_draft).as_ref() {
+                        Some((file_name, backup)) => {
+                            render_backup_dialog(
+                                file_name,
+                                backup,
+                                is_known_backup(&persisted_state, backup),
+                                &on_apply_backup,
+                                &on_cancel_backup,
+                            )
+                        }
+                        None => html! {},
+                    }
+                }
+                <main class="content single-column">
+                    {
+                        match (*storage_notice).as_ref() {
+                            Some(notice) => {
+                                let storage_notice = storage_notice.clone();
+                                html! {
+                                    <div class="list-notice storage-notice" data-swipe-ignore="true">
+                                        <p>{ notice }</p>
+                                        <button onclick={Callback::from(move |_| storage_notice.set(None))}>
+                                            { "Dismiss" }
+                                        </button>
+                                    </div>
+                                }
+                            }
+                            None => html! {},
+                        }
+                    }
+                    {
+                        match (*list_notice).as_ref() {
+                            Some(notice) => html! { <p class="list-notice">{ notice }</p> },
+                            None => html! {},
+                        }
+                    }
+                    { matchup_markup }
+                    {
+                        if matches!(*items_status, FetchStatus::Error(_)) && selected_list.is_some() {
+                            html! {
+                                <button class="retry-button" data-swipe-ignore="true" onclick={on_retry_list}>
+                                    { "Try again" }
+                                </button>
+                            }
+                        } else {
+                            html! {}
+                        }
+                    }
+                </main>
+            </div>
+        </>
+    }
+}
+
+/// What the menu shows, borrowed from the app's state for one render.
+struct MenuView<'a> {
+    open: bool,
+    lists_expanded: bool,
+    history_expanded: bool,
+    list_search: &'a str,
+    show_reset_confirm: bool,
+    status: &'a FetchStatus,
+    lists: Option<&'a [ListInfo]>,
+    selected_list: Option<&'a str>,
+    loaded: Option<&'a LoadedList>,
+    ranking: Option<&'a dyn RatingEngine>,
+    list_state: Option<&'a StoredListState>,
+    custom_lists: &'a [CustomList],
+    cached_lists: &'a [CachedList],
+    shared_evidence: bool,
+}
+
+/// How the menu's controls reach back into the app.
+struct MenuCallbacks {
+    on_close: Callback<()>,
+    on_toggle_lists: Callback<()>,
+    on_search_lists: Callback<String>,
+    on_select_list: Callback<String>,
+    on_new_list: Callback<()>,
+    on_edit_list: Callback<String>,
+    on_import_file: Callback<File>,
+    on_forget_list: Callback<String>,
+    on_request_reset: Callback<()>,
+    on_cancel_reset: Callback<()>,
+    on_confirm_reset: Callback<()>,
+    on_set_filter: Callback<String>,
+    on_select_mode: Callback<MatchupMode>,
+    on_set_top_k: Callback<usize>,
+    on_select_engine: Callback<RatingEngineKind>,
+    on_set_shared_evidence: Callback<bool>,
+    on_toggle_history: Callback<()>,
+    on_edit_history: Callback<HistoryAction>,
+    on_export_ranking: Callback<ExportFormat, Option<(String, String)>>,
+    on_export_backup: Callback<()>,
+    on_import_backup: Callback<File>,
+}
+
+fn render_menu(view: &MenuView, callbacks: &MenuCallbacks) -> Html {
+    let overlay_classes = classes!("menu-overlay", if view.open { Some("open") } else { None });
+    let panel_classes = classes!("menu-panel", if view.open { Some("open") } else { None });
+    let stop_click = Callback::from(|event: web_sys::MouseEvent| event.stop_propagation());
+    let close_click = {
+        let on_close = callbacks.on_close.clone();
+        Callback::from(move |_| on_close.emit(()))
+    };
+    let toggle_lists_click = {
+        let on_toggle_lists = callbacks.on_toggle_lists.clone();
+        Callback::from(move |_| on_toggle_lists.emit(()))
+    };
+
+    let list_section = (view.list_state, view.loaded);
+    let filter_section = match list_section {
+        (Some(state), Some(list)) => {
+            render_item_filter(state, &list.items, &callbacks.on_set_filter)
+        }
+        _ => html! {},
+    };
+    let history_section = match list_section {
+        (Some(state), Some(list)) => render_history(
+            state,
+            &list.items,
+            view.history_expanded,
+            &callbacks.on_toggle_history,
+            &callbacks.on_edit_history,
+        ),
+        _ => html! {},
+    };
+    let settings_section = match view.list_state {
+        Some(state) => render_settings(state, view.shared_evidence, callbacks),
+        None => html! {},
+    };
+
+    html! {
+        <div class={overlay_classes} data-swipe-ignore="true" onclick={close_click.clone()}>
+            <aside class={panel_classes} data-swipe-ignore="true" onclick={stop_click}>
+                <div class="menu-header">
+                    <h2>{ "Menu" }</h2>
+                    <button class="menu-close" onclick={close_click}>{ "×" }</button>
+                </div>
+
+                <div class="menu-section">
+                    <button class={classes!("menu-toggle", if view.lists_expanded { "expanded" } else { "" })}
+                        onclick={toggle_lists_click}>
+                        <span>{ "Lists" }</span>
+                        <span class="chevron">{ if view.lists_expanded { "▾" } else { "▸" } }</span>
+                    </button>
+                    {
+                        if view.lists_expanded {
+                            render_list_picker(view, callbacks)
+                        } else {
+                            html! {}
+                        }
+                    }
+                </div>
+
+                { view.loaded.map(render_list_details).unwrap_or_default() }
+
+                { render_reset(view.show_reset_confirm, callbacks) }
+
+                { filter_section }
+
+                { settings_section }
+
+                { history_section }
+
+                { render_rankings(view, &callbacks.on_export_ranking) }
+
+                { render_backup_section(callbacks) }
+            </aside>
+        </div>
+    }
+}
+
+/// Bundled lists grouped by category, then the lists made or opened here,
+/// all narrowed by the search box.
+fn render_list_picker(view: &MenuView, callbacks: &MenuCallbacks) -> Html {
+    let list_search = view.list_search;
+    let on_select_list = &callbacks.on_select_list;
+    let current_selection = view.selected_list.map(str::to_string);
+
+    let mut search_hits = 0;
+    let bundled_lists = match view.status {
+        FetchStatus::Loading => html! { <p class="menu-placeholder">{ "Loading lists..." }</p> },
+        FetchStatus::Error(message) => html! { <p class="menu-error">{ message }</p> },
+        FetchStatus::Idle => match view.lists {
+            Some(list_vec) if !list_vec.is_empty() => {
+                let groups = group_by_category(
+                    list_vec
+                        .iter()
+                        .filter(|info| info.matches_search(list_search)),
+                );
+                search_hits += groups.iter().map(|(_, infos)| infos.len()).sum::<usize>();
+                let group_count = groups.len();
+                html! {
+                    { for groups.into_iter().map(|(category, infos)| {
+                        let heading = match category {
+                            Some(name) => html! { <h3 class="menu-subheading">{ name }</h3> },
+                            None if group_count > 1 => {
+                                html! { <h3 class="menu-subheading">{ "Other lists" }</h3> }
+                            }
+                            None => html! {},
+                        };
+                        html! {
+                            <div class="list-group">
+                                { heading }
+                                <div class="menu-list-buttons">
+                                    { for infos.into_iter().map(|info| render_list_button(info, &current_selection, on_select_list)) }
+                                </div>
+                            </div>
+                        }
+                    }) }
+                }
+            }
+            _ => html! { <p class="menu-placeholder">{ "No lists available." }</p> },
+        },
+    };
+
+    let custom_lists: Vec<&CustomList> = view
+        .custom_lists
+        .iter()
+        .filter(|list| list.info().matches_search(list_search))
+        .collect();
+    let cached_lists: Vec<&CachedList> = view
+        .cached_lists
+        .iter()
+        .filter(|list| list.info().matches_search(list_search))
+        .collect();
+    search_hits += custom_lists.len() + cached_lists.len();
+    let custom_rows = custom_lists.iter().map(|list| {
+        let on_edit_list = callbacks.on_edit_list.clone();
+        let id = list.id.clone();
+        html! {
+            <div class="custom-list-row">
+                { render_list_button(&list.info(), &current_selection, on_select_list) }
+                <button class="list-edit" onclick={Callback::from(move |_| on_edit_list.emit(id.clone()))}>
+                    { "Edit" }
+                </button>
+            </div>
+        }
+    });
+    let cached_rows = cached_lists.iter().map(|list| {
+        let on_forget_list = callbacks.on_forget_list.clone();
+        let id = list.id.clone();
+        html! {
+            <div class="custom-list-row">
+                { render_list_button(&list.info(), &current_selection, on_select_list) }
+                <button class="list-edit" onclick={Callback::from(move |_| on_forget_list.emit(id.clone()))}>
+                    { "Forget" }
+                </button>
+            </div>
+        }
+    });
+    let new_list_click = {
+        let on_new_list = callbacks.on_new_list.clone();
+        Callback::from(move |_| on_new_list.emit(()))
+    };
+    let import_change = {
+        let on_import_file = callbacks.on_import_file.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                on_import_file.emit(file);
+            }
+            // Lets the same file be picked again after fixing it.
+            input.set_value("");
+        })
+    };
+    let on_search_input = {
+        let on_search_lists = callbacks.on_search_lists.clone();
+        Callback::from(move |event: InputEvent| {
+            on_search_lists.emit(event.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    html! {
+        <>
+            <input type="search"
+                class="list-search"
+                placeholder="Search lists"
+                value={list_search.to_string()}
+                oninput={on_search_input} />
+            {
+                if search_hits == 0 && !list_search.trim().is_empty() {
+                    html! {
+                        <p class="menu-placeholder">
+                            { format!("No lists match \"{}\".", list_search.trim()) }
+                        </p>
+                    }
+                } else {
+                    html! {}
+                }
+            }
+            <div class="list-groups">{ bundled_lists }</div>
+            <h3 class="menu-subheading">{ "Your lists" }</h3>
+            {
+                if custom_lists.is_empty() && cached_lists.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <div class="menu-list-buttons">
+                            { for custom_rows }
+                            { for cached_rows }
+                        </div>
+                    }
+                }
+            }
+            <button class="menu-action new-list" onclick={new_list_click}>{ "+ New list" }</button>
+            <label class="menu-action import-file">
+                { "Import CSV, TSV or text file" }
+                <input type="file"
+                    accept=".csv,.tsv,.tab,.txt,text/csv,text/tab-separated-values,text/plain"
+                    onchange={import_change} />
+            </label>
+            <OpenUrlForm on_open={on_select_list.clone()} />
+        </>
+    }
+}
+
+/// Description, metadata and share link of the loaded list, when it has any.
+fn render_list_details(list: &LoadedList) -> Html {
+    let info = &list.info;
+    let meta: Vec<String> = [
+        info.author.as_ref().map(|author| format!("by {author}")),
+        info.version.as_ref().map(|version| format!("v{version}")),
+        info.locale.as_ref().map(|locale| locale.to_uppercase()),
+        (!list.sources.is_empty()).then(|| format!("combines {} lists", list.sources.len())),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let share_link = remote_list_url(&info.id).and_then(share_link);
+    if info.description.is_none() && meta.is_empty() && share_link.is_none() {
+        return html! {};
+    }
+    html! {
+        <div class="menu-section list-details">
+            <h3 class="menu-subheading">{ &info.label }</h3>
+            {
+                match info.description.as_ref() {
+                    Some(description) => html! {
+                        <p class="list-description">{ description }</p>
+                    },
+                    None => html! {},
+                }
+            }
+            {
+                if meta.is_empty() {
+                    html! {}
+                } else {
+                    html! { <p class="list-meta">{ meta.join(" · ") }</p> }
+                }
+            }
+            {
+                match share_link {
+                    Some(link) => html! {
+                        <label class="share-link">
+                            { "Share link" }
+                            <input type="text" readonly=true value={link} />
+                        </label>
+                    },
+                    None => html! {},
+                }
+            }
+        </div>
+    }
+}
+
+fn render_reset(show_confirm: bool, callbacks: &MenuCallbacks) -> Html {
+    let emit = |callback: &Callback<()>| {
+        let callback = callback.clone();
+        Callback::from(move |_| callback.emit(()))
+    };
+    html! {
+        <div class="menu-section">
+            {
+                if show_confirm {
+                    html! {
+                        <div class="reset-confirm">
+                            <p>{ "Are you sure you want to reset the rankings?" }</p>
+                            <div class="confirm-actions">
+                                <button class="confirm-yes" onclick={emit(&callbacks.on_confirm_reset)}>{ "Yes" }</button>
+                                <button class="confirm-no" onclick={emit(&callbacks.on_cancel_reset)}>{ "No" }</button>
+                            </div>
+                        </div>
+                    }
+                } else {
+                    html! {
+                        <button class="menu-action reset" onclick={emit(&callbacks.on_request_reset)}>{ "Reset Rankings" }</button>
+                    }
+                }
+            }
+        </div>
+    }
+}
+
+fn render_item_filter(
+    state: &StoredListState,
+    items: &[ListItem],
+    on_set_filter: &Callback<String>,
+) -> Html {
+    let filter = ItemFilter::parse(&state.filter);
+    let matching = items.iter().filter(|item| filter.matches(item)).count();
+    let on_change = {
+        let on_set_filter = on_set_filter.clone();
+        Callback::from(move |event: Event| {
+            let input: HtmlInputElement = event.target_unchecked_into();
+            on_set_filter.emit(input.value());
+        })
+    };
+    let tag_buttons = list_tags(items)
+        .into_iter()
+        .take(MAX_TAG_SUGGESTIONS)
+        .map(|tag| {
+            let query = format!("#{tag}");
+            let active = state.filter == query;
+            let on_set_filter = on_set_filter.clone();
+            let next = if active { String::new() } else { query };
+            html! {
+                <button class={classes!("tag-chip", if active { Some("active") } else { None })}
+                    onclick={Callback::from(move |_| on_set_filter.emit(next.clone()))}>
+                    { format!("#{tag}") }
+                </button>
+            }
+        });
+    html! {
+        <div class="menu-section item-filter">
+            <h3 class="menu-subheading">{ "Filter items" }</h3>
+            <input type="search"
+                placeholder="#tag, ^M, word, -#exclude"
+                value={state.filter.clone()}
+                onchange={on_change} />
+            <div class="tag-chips">{ for tag_buttons }</div>
+            {
+                if filter.is_empty() {
+                    html! {}
+                } else {
+                    html! {
+                        <p class="filter-status">
+                            { format!("{} of {} items match; other results still count", matching, items.len()) }
+                        </p>
+                    }
+                }
+            }
+        </div>
+    }
+}
+
+/// Matchup strategy, rating engine and shared evidence.
+fn render_settings(
+    state: &StoredListState,
+    shared_evidence: bool,
+    callbacks: &MenuCallbacks,
+) -> Html {
+    html! {
+        <div class="menu-section">
+            <h3 class="menu-subheading">{ "Matchup strategy" }</h3>
+            { render_choice_buttons(&MatchupMode::ALL, state.mode, MatchupMode::label, &callbacks.on_select_mode) }
+            {
+                match state.top_k.as_ref() {
+                    Some(top_k) => {
+                        let on_set_top_k = callbacks.on_set_top_k.clone();
+                        let on_change = Callback::from(move |event: Event| {
+                            let input: web_sys::HtmlInputElement = event.target_unchecked_into();
+                            if let Ok(k) = input.value().trim().parse::<usize>() {
+                                on_set_top_k.emit(k);
+                            }
+                        });
+                        html! {
+                            <label class="top-k-input">
+                                { "Items to find" }
+                                <input type="number" min="1" value={top_k.k().to_string()} onchange={on_change} />
+                            </label>
+                        }
+                    }
+                    None => html! {},
+                }
+            }
+            <h3 class="menu-subheading">{ "Rating engine" }</h3>
+            { render_choice_buttons(&RatingEngineKind::ALL, state.engine, RatingEngineKind::label, &callbacks.on_select_engine) }
+            <h3 class="menu-subheading">{ "Comparisons apply to" }</h3>
+            { render_choice_buttons(&[false, true], shared_evidence, shared_evidence_label, &callbacks.on_set_shared_evidence) }
+        </div>
+    }
+}
+
+/// The current ranking with the fit and mode progress above it and the
+/// export controls below.
+fn render_rankings(
+    view: &MenuView,
+    on_export_ranking: &Callback<ExportFormat, Option<(String, String)>>,
+) -> Html {
+    let total_matches = view
+        .list_state
+        .map(|state| state.total_matches())
+        .unwrap_or(0);
+
+    let intervals = view
+        .ranking
+        .map(|ranking| ranking.rating_intervals())
+        .unwrap_or_default();
+    let mode_status = match view.list_state {
+        Some(state) => match (state.sort.as_ref(), state.top_k.as_ref()) {
+            (Some(sort), _) if sort.is_complete() => html! {
+                <p class="sort-status complete">{ "Exact order complete" }</p>
+            },
+            (Some(sort), _) => html! {
+                <p class="sort-status">
+                    { format!("Exact order: at most {} comparisons remaining", sort.comparisons_remaining()) }
+                </p>
+            },
+            (None, Some(top_k)) if top_k.is_complete(&intervals) => html! {
+                <p class="sort-status complete">{ format!("Top {} identified", top_k.k()) }</p>
+            },
+            (None, Some(top_k)) => html! {
+                <p class="sort-status">
+                    { format!(
+                        "Top {}: {} contenders left, {} eliminated",
+                        top_k.k(),
+                        top_k.contenders(&intervals).len(),
+                        top_k.eliminated_count()
+                    ) }
+                </p>
+            },
+            (None, None) => html! {},
+        },
+        None => html! {},
+    };
+
+    let fit_status = match view.ranking.and_then(|ranking| ranking.fit_report()) {
+        Some(report) => {
+            let since = if report.stale {
+                "; newer comparisons are only partly fitted"
+            } else {
+                ""
+            };
+            if report.converged {
+                html! {
+                    <p class="fit-status converged">
+                        { format!(
+                            "Converged after {} iterations (max change {:.1e}){}",
+                            report.iterations, report.max_delta, since
+                        ) }
+                    </p>
+                }
+            } else {
+                html! {
+                    <p class="fit-status unconverged">
+                        { format!(
+                            "Not converged after {} iterations (max change {:.1e}); ratings are still settling{}",
+                            report.iterations, report.max_delta, since
+                        ) }
+                    </p>
+                }
+            }
+        }
+        None => html! {},
+    };
+
+    let rankings = if let (Some(list), Some(ranking), Some(state)) =
+        (view.loaded, view.ranking, view.list_state)
+    {
+        let total_opponents = list.items.len().saturating_sub(1) as f64;
+        let items_with_scores: Vec<_> = ranked_indices(&list.items, state, ranking)
+            .into_iter()
+            .map(|index| {
+                let item = &list.items[index];
+                let rating = ranking.display_rating(index);
+                let margin = ranking.rating_margin(index);
+                let matches = state.match_totals.get(index).copied().unwrap_or(0);
+                let matches_f = matches as f64;
+                let confidence = if matches >= 1 && total_opponents > 1.0 {
+                    let variance_component = (0.25 / matches_f).sqrt();
+                    let coverage =
+                        ((total_opponents - matches_f).max(0.0) / (total_opponents - 1.0)).sqrt();
+                    let interval = 1.96 * variance_component * coverage;
+                    (1.0 - interval).clamp(0.0, 1.0).powf(2.0)
+                } else {
+                    0.0
+                };
+                (
+                    item.id.clone(),
+                    item.label.clone(),
+                    rating,
+                    margin,
+                    confidence,
+                    state
+                        .top_k
+                        .as_ref()
+                        .is_some_and(|top_k| top_k.is_eliminated(index)),
+                )
+            })
+            .collect();
+
+        html! {
+            <ul class="menu-ranking-list">
+                { for items_with_scores.into_iter().map(|(id, label, rating, margin, confidence, eliminated)| {
+                    let fill_percent = (confidence * 100.0).clamp(0.0, 100.0);
+                    html! {
+                        <li key={id}
+                            class={classes!(if eliminated { Some("eliminated") } else { None })}
+                            style={format!("--confidence-fill: {:.2}%;", fill_percent)}>
+                            <span class="item-label">
+                                { label }
+                                {
+                                    if eliminated {
+                                        html! { <span class="item-badge">{ "eliminated" }</span> }
+                                    } else {
+                                        html! {}
+                                    }
+                                }
+                            </span>
+                            <span class="item-rating">
+                                { format!("{rating:.0}") }
+                                {
+                                    match margin {
+                                        Some(margin) => html! {
+                                            <span class="item-margin">{ format!(" ± {margin:.0}") }</span>
+                                        },
+                                        None => html! {},
+                                    }
+                                }
+                            </span>
+                        </li>
+                    }
+                }) }
+            </ul>
+        }
+    } else {
+        html! { <p class="menu-placeholder">{ "Rankings will appear once a list is loaded." }</p> }
+    };
+
+    html! {
+        <div class="menu-section rankings">
+            <div class="menu-section-header">
+                <h3>{ "Current Rankings" }</h3>
+                <span class="matches-count">{ format!("Matches recorded: {total_matches}") }</span>
+            </div>
+            { fit_status }
+            { mode_status }
+            <div class="ranking-scroll">
+                { rankings }
+            </div>
+            {
+                if total_matches > 0 {
+                    html! { <RankingExport export={on_export_ranking.clone()} /> }
+                } else {
+                    html! {}
+                }
+            }
+        </div>
+    }
+}
+
+fn render_backup_section(callbacks: &MenuCallbacks) -> Html {
+    let export_backup_click = {
+        let on_export_backup = callbacks.on_export_backup.clone();
+        Callback::from(move |_| on_export_backup.emit(()))
+    };
+    let import_backup_change = {
+        let on_import_backup = callbacks.on_import_backup.clone();
+        Callback::from(move |event: Event| {
+            let input = event.target_unchecked_into::<HtmlInputElement>();
+            if let Some(file) = input.files().and_then(|files| files.get(0)) {
+                on_import_backup.emit(file);
+            }
+            input.set_value("");
+        })
+    };
+    html! {
+        <div class="menu-section backup">
+            <h3 class="menu-subheading">{ "Backup" }</h3>
+            <button class="menu-action export" onclick={export_backup_click}>
+                { "Export backup" }
+            </button>
+            <label class="menu-action import-file">
+                { "Import backup" }
+                <input type="file" accept=".json,application/json" onchange={import_backup_change} />
+            </label>
+        </div>
+    }
+}
+
+/// Asks whether an imported backup is merged with the progress saved here or
+/// replaces it.
+fn render_backup_dialog(
+    file_name: &str,
+    backup: &Backup,
+    already_merged: bool,
+    on_apply: &Callback<ImportMode>,
+    on_cancel: &Callback<()>,
+) -> Html {
+    let backup = &backup.state;
+    let with_progress = backup
+        .lists
+        .values()
+        .filter(|list| list.total_matches() > 0)
+        .count();
+    let summary = format!(
+        "{} lists with progress, {} made in the app, {} opened from a URL.",
+        with_progress,
+        backup.custom_lists.len(),
+        backup.cached_lists.len()
+    );
+    let apply = |mode: ImportMode| {
+        let on_apply = on_apply.clone();
+        Callback::from(move |_| on_apply.emit(mode))
+    };
+    let cancel_click = {
+        let on_cancel = on_cancel.clone();
+        Callback::from(move |_| on_cancel.emit(()))
+    };
+
+    html! {
+        <div class="editor-overlay" data-swipe-ignore="true">
+            <div class="list-editor">
+                <h2>{ format!("Import {}", file_name) }</h2>
+                <p class="editor-count">{ summary }</p>
+                <p class="editor-hint">
+                    {
+                        if already_merged {
+                            "This backup was made here or has already been merged, so merging it again would change nothing. Replace discards everything saved here first."
+                        } else {
+                            "Merge adds its comparisons to the ones saved here. Replace discards everything saved here first."
+                        }
+                    }
+                </p>
+                <div class="editor-actions">
+                    <button class="editor-cancel" onclick={cancel_click}>{ "Cancel" }</button>
+                    <button class="editor-delete" onclick={apply(ImportMode::Replace)}>{ "Replace" }</button>
+                    <button class="editor-save" disabled={already_merged} onclick={apply(ImportMode::Merge)}>{ "Merge" }</button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+struct RankingExportProps {
+    /// File name and contents of the current ranking in a format, or `None`
+    /// while no list is loaded.
+    export: Callback<ExportFormat, Option<(String, String)>>,
+}
+
+/// Format choice with download and copy buttons for the current ranking.
+#[function_component(RankingExport)]
+fn ranking_export(props: &RankingExportProps) -> Html {
+    let format = use_state(|| ExportFormat::Csv);
+    let status = use_state(|| None::<String>);
+
+    let on_format = {
+        let format = format.clone();
+        let status = status.clone();
+        Callback::from(move |next: ExportFormat| {
+            format.set(next);
+            status.set(None);
+        })
+    };
+    let download_click = {
+        let export = props.export.clone();
+        let format = format.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let Some((file_name, text)) = export.emit(*format) else {
+                return;
+            };
+            match download_text(&file_name, format.mime_type(), &text) {
+                Ok(()) => status.set(None),
+                Err(err) => {
+                    log::warn!("Failed to export ranking: {:?}", err);
+                    status.set(Some("The download could not be started.".to_string()));
+                }
+            }
+        })
+    };
+    let copy_click = {
+        let export = props.export.clone();
+        let format = format.clone();
+        let status = status.clone();
+        Callback::from(move |_| {
+            let Some((_, text)) = export.emit(*format) else {
+                return;
+            };
+            let status = status.clone();
+            spawn_local(async move {
+                let message = match copy_text(&text).await {
+                    Ok(()) => "Copied to the clipboard.",
+                    Err(err) => {
+                        log::warn!("Failed to copy ranking: {:?}", err);
+                        "The browser did not allow copying."
+                    }
+                };
+                status.set(Some(message.to_string()));
+            });
+        })
+    };
+
+    html! {
+        <div class="ranking-export">
+            <h3 class="menu-subheading">{ "Export ranking" }</h3>
+            { render_choice_buttons(&ExportFormat::ALL, *format, ExportFormat::label, &on_format) }
+            <div class="ranking-export-actions">
+                <button onclick={download_click}>{ "Download" }</button>
+                <button onclick={copy_click}>{ "Copy" }</button>
+            </div>
+            {
+                match (*status).as_ref() {
+                    Some(message) => html! { <p class="export-status">{ message }</p> },
+                    None => html! {},
+                }
+            }
+        </div>
+    }
+}
+
+async fn copy_text(text: &str) -> Result<(), JsValue> {
+    let window = window().ok_or_else(|| JsValue::from_str("no window"))?;
+    JsFuture::from(window.navigator().clipboard().write_text(text)).await?;
+    Ok(())
+}
+
+/// Offers `text` to the browser as a file download.
+fn download_text(file_name: &str, mime: &str, text: &str) -> Result<(), JsValue> {
+    let parts = js_sys::Array::of1(&JsValue::from_str(text));
+    let options = BlobPropertyBag::new();
+    options.set_type(mime);
+    let blob = Blob::new_with_str_sequence_and_options(&parts, &options)?;
+    let url = Url::create_object_url_with_blob(&blob)?;
+    let document = window()
+        .and_then(|window| window.document())
+        .ok_or_else(|| JsValue::from_str("no document"))?;
+    let link: HtmlAnchorElement = document.create_element("a")?.dyn_into()?;
+    link.set_href(&url);
+    link.set_download(file_name);
+    link.click();
+    // Some browsers start the download only after the click handler returns.
+    Timeout::new(DOWNLOAD_REVOKE_DELAY_MS, move || {
+        let _ = Url::revoke_object_url(&url);
+    })
+    .forget();
+    Ok(())
+}
+
+/// Image (when it loads), title and subtitle of one matchup card. Images that
+/// fail are remembered so the card falls back to text.
+fn render_card_content(
+    item: &ListItem,
+    failed_images: &HashSet<String>,
+    on_image_error: &Callback<String>,
+) -> Html {
+    let image = item
+        .image
+        .as_deref()
+        .map(resolve_image_url)
+        .filter(|url| !failed_images.contains(url));
+    let image_markup = match image {
+        Some(url) => {
+            let on_image_error = on_image_error.clone();
+            let failed_url = url.clone();
+            let onerror = Callback::from(move |_: Event| on_image_error.emit(failed_url.clone()));
+            html! {
+                <img class="card-image"
+                    src={url}
+                    alt={item.label.clone()}
+                    draggable="false"
+                    {onerror} />
+            }
+        }
+        None => html! {},
+    };
+    let subtitle = match item.subtitle.as_ref() {
+        Some(subtitle) => html! { <p class="card-subtitle">{ subtitle }</p> },
+        None => html! {},
+    };
+
+    html! {
+        <>
+            { image_markup }
+            <p class="card-title">{ &item.label }</p>
+            { subtitle }
+        </>
+    }
+}
+
+/// Warms the browser cache with the images of the pairs that would follow
+/// `current` for either decisive outcome.
+///
+/// The prediction borrows the list's state: only the match totals and the
+/// exact-order sort are copied to fold the outcome in. The matrices and the
+/// ratings are used as they stand, which only misses the one extra game of
+/// the pair just shown.
+fn preload_next_images(
+    list: &LoadedList,
+    state: &StoredListState,
+    ranking: &dyn RatingEngine,
+    history: &[Matchup],
+    current: &Matchup,
+    rng: &StdRng,
+) {
+    let mut history = history.to_vec();
+    history.push(current.clone());
+    for winner in [current.left_index, current.right_index] {
+        let mut match_totals = state.match_totals.clone();
+        for index in [current.left_index, current.right_index] {
+            if let Some(total) = match_totals.get_mut(index) {
+                *total = total.saturating_add(1);
+            }
+        }
+        let sort = state.sort.clone().map(|mut sort| {
+            sort.record(current.left_index, current.right_index, Some(winner));
+            sort
+        });
+        let strategy: &dyn MatchupStrategy = match (&sort, state.mode) {
+            (Some(sort), MatchupMode::ExactOrder) => sort,
+            _ => match state.strategy() {
+                Some(strategy) => strategy,
+                None => continue,
+            },
+        };
+        let Some(next) = matchup_for(
+            strategy,
+            state,
+            &match_totals,
+            &list.items,
+            ranking,
+            &history,
+            &mut rng.clone(),
+        ) else {
+            continue;
+        };
+        for index in [next.left_index, next.right_index] {
+            if let Some(path) = list.items.get(index).and_then(|item| item.image.as_ref()) {
+                if let Ok(image) = HtmlImageElement::new() {
+                    image.set_src(&resolve_image_url(path));
+                }
+            }
+        }
+    }
+}
+
+/// The list's own decisions, newest first, each of which can be taken back
+/// or given to the other item.
+fn render_history(
+    state: &StoredListState,
+    items: &[ListItem],
+    expanded: bool,
+    on_toggle: &Callback<()>,
+    on_edit: &Callback<HistoryAction>,
+) -> Html {
+    let own: Vec<(usize, &MatchEvent)> = state
+        .events
+        .iter()
+        .enumerate()
+        .filter(|(_, event)| event.source.is_none())
+        .collect();
+    let toggle_click = {
+        let on_toggle = on_toggle.clone();
+        Callback::from(move |_| on_toggle.emit(()))
+    };
+    let label = |id: &str| {
+        items
+            .iter()
+            .find(|item| item.id == id)
+            .map_or_else(|| id.to_string(), |item| item.label.clone())
+    };
+    let rows = own
+        .iter()
+        .rev()
+        .take(MAX_HISTORY_ROWS)
+        .map(|&(index, event)| {
+            let outcome = if event.tie {
+                format!("{} and {} tied", label(&event.winner), label(&event.loser))
+            } else {
+                format!("{} beat {}", label(&event.winner), label(&event.loser))
+            };
+            let time = if event.at == 0 {
+                String::new()
+            } else {
+                js_sys::Date::new(&JsValue::from_f64(event.at as f64))
+                    .to_locale_string("default", &JsValue::UNDEFINED)
+                    .into()
+            };
+            let flip_button = if event.tie {
+                html! {}
+            } else {
+                let on_edit = on_edit.clone();
+                html! {
+                    <button onclick={Callback::from(move |_| on_edit.emit(HistoryAction::Flip(index)))}>
+                        { "Flip" }
+                    </button>
+                }
+            };
+            let revert_click = {
+                let on_edit = on_edit.clone();
+                Callback::from(move |_| on_edit.emit(HistoryAction::Revert(index)))
+            };
+            html! {
+                <li class="history-row">
+                    <span class="history-outcome">{ outcome }</span>
+                    <span class="history-time">{ time }</span>
+                    <div class="history-actions">
+                        { flip_button }
+                        <button onclick={revert_click}>{ "Revert" }</button>
+                    </div>
+                </li>
+            }
+        });
+
+    html! {
+        <div class="menu-section decision-history">
+            <button class={classes!("menu-toggle", if expanded { "expanded" } else { "" })}
+                onclick={toggle_click}>
+                <span>{ format!("Decision history ({})", own.len()) }</span>
+                <span class="chevron">{ if expanded { "▾" } else { "▸" } }</span>
+            </button>
+            {
+                if !expanded {
+                    html! {}
+                } else if own.is_empty() {
+                    html! { <p class="history-note">{ "No decisions in this list yet." }</p> }
+                } else {
+                    html! {
+                        <>
+                            <ul class="history-rows">{ for rows }</ul>
+                            {
+                                if own.len() > MAX_HISTORY_ROWS {
+                                    html! {
+                                        <p class="history-note">
+                                            { format!("Showing the latest {} of {}; undo reaches all of them.", MAX_HISTORY_ROWS, own.len()) }
+                                        </p>
+                                    }
+                                } else {
+                                    html! {}
+                                }
+                            }
+                        </>
+                    }
+                }
+            }
+        </div>
+    }
+}
+
+fn render_choice_buttons<T: Copy + PartialEq + 'static>(
+    options: &[T],
+    selected: T,
+    label: fn(T) -> &'static str,
+    on_select: &Callback<T>,
+) -> Html {
+    html! {
+        <div class="choice-options">
+            { for options.iter().map(|&option| {
+                let on_select = on_select.clone();
+                let class = classes!(
+                    "choice-option",
+                    if option == selected { Some("active") } else { None }
+                );
+                html! {
+                    <button class={class} onclick={Callback::from(move |_| on_select.emit(option))}>
+                        { label(option) }
+                    </button>
+                }
+            }) }
+        </div>
+    }
+}
+
+fn shared_evidence_label(enabled: bool) -> &'static str {
+    if enabled {
+        "Every list with both"
+    } else {
+        "This list"
+    }
+}
+
+/// Next pair for the list's strategy, drawn from the items that pass the
+/// list's filter.
+fn select_matchup(
+    state: &StoredListState,
+    items: &[ListItem],
+    ranking: &dyn RatingEngine,
+    history: &[Matchup],
+    rng: &mut dyn RngCore,
+) -> Option<Matchup> {
+    let strategy = state.strategy()?;
+    matchup_for(
+        strategy,
+        state,
+        &state.match_totals,
+        items,
+        ranking,
+        history,
+        rng,
+    )
+}
+
+/// [`select_matchup`] with the strategy and match totals given separately,
+/// so a look-ahead can swap in its own copies.
+fn matchup_for(
+    strategy: &dyn MatchupStrategy,
+    state: &StoredListState,
+    match_totals: &[u32],
+    items: &[ListItem],
+    ranking: &dyn RatingEngine,
+    history: &[Matchup],
+    rng: &mut dyn RngCore,
+) -> Option<Matchup> {
+    let context = MatchupContext::new(ranking, &state.win_matrix, &state.tie_matrix, match_totals)
+        .with_allowed(ItemFilter::parse(&state.filter).mask(items));
+    strategy.next_matchup(&context, history, rng)
+}
+
+fn loaded_items(loaded_list: &Option<LoadedList>) -> &[ListItem] {
+    loaded_list
+        .as_ref()
+        .map(|list| list.items.as_slice())
+        .unwrap_or(&[])
+}
+
+fn completion_message(
+    list_state: &UseStateHandle<Option<StoredListState>>,
+    ranking_state: &UseStateHandle<Option<Box<dyn RatingEngine>>>,
+) -> Option<String> {
+    let state = (**list_state).as_ref()?;
+    let message = match state.mode {
+        MatchupMode::Adaptive | MatchupMode::InformationGain => None,
+        MatchupMode::ExactOrder => state
+            .sort
+            .as_ref()
+            .filter(|sort| sort.is_complete())
+            .map(|_| "Exact order complete. Open the menu to see the final ranking.".to_string()),
+        MatchupMode::TopK => {
+            let ranking = (**ranking_state).as_ref()?;
+            state
+                .top_k
+                .as_ref()
+                .filter(|top_k| top_k.is_complete(&ranking.rating_intervals()))
+                .map(|top_k| format!("Top {} identified. Open the menu to see them.", top_k.k()))
+        }
+    };
+    message.or_else(|| {
+        (!ItemFilter::parse(&state.filter).is_empty())
+            .then(|| "No pairs left within the filter. Change or clear it in the menu.".to_string())
+    })
+}
+
+fn render_list_button(
+    info: &ListInfo,
+    current_selection: &Option<String>,
+    on_select_list: &Callback<String>,
+) -> Html {
+    let id = info.id.clone();
+    let label = info.label.clone();
+    let is_active = current_selection
+        .as_ref()
+        .map(|selected| selected == &info.id)
+        .unwrap_or(false);
+
+    let class = if is_active {
+        "list-button active"
+    } else {
+        "list-button"
+    };
+
+    let on_click = {
+        let on_select_list = on_select_list.clone();
+        Callback::from(move |_| {
+            on_select_list.emit(id.clone());
+        })
+    };
+
+    let details: Vec<String> = [
+        info.item_count.map(|count| format!("{count} items")),
+        info.updated.as_ref().map(|date| format!("updated {date}")),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    html! {
+        <button class={class} onclick={on_click}>
+            {
+                match info.thumbnail.as_deref() {
+                    Some(path) => html! {
+                        <img class="list-thumb" src={resolve_image_url(path)} alt="" loading="lazy" />
+                    },
+                    None => html! {},
+                }
+            }
+            <span class="list-button-text">
+                <span class="list-button-label">{ label }</span>
+                {
+                    match info.description.as_ref() {
+                        Some(description) => html! {
+                            <span class="list-button-description">{ description }</span>
+                        },
+                        None => html! {},
+                    }
+                }
+                {
+                    if details.is_empty() {
+                        html! {}
+                    } else {
+                        html! { <span class="list-button-meta">{ details.join(" · ") }</span> }
+                    }
+                }
+            </span>
+        </button>
+    }
+}
+
+/// Splits lists into runs that share a category; the index already orders
+/// them by group.
+fn group_by_category<'a>(
+    lists: impl Iterator<Item = &'a ListInfo>,
+) -> Vec<(Option<&'a str>, Vec<&'a ListInfo>)> {
+    let mut groups: Vec<(Option<&str>, Vec<&ListInfo>)> = Vec::new();
+    for info in lists {
+        let category = info.category.as_deref();
+        match groups.last_mut() {
+            Some((last, members)) if *last == category => members.push(info),
+            _ => groups.push((category, vec![info])),
+        }
+    }
+    groups
+}
+
+#[allow(clippy::too_many_arguments)]
+fn render_matchup_area(
+    status: &UseStateHandle<FetchStatus>,
+    loaded: &UseStateHandle<Option<LoadedList>>,
+    current_match: &UseStateHandle<Option<Matchup>>,
+    drag_state: &UseStateHandle<Option<DragState>>,
+    card_transition: &UseStateHandle<CardTransition>,
+    flash_side: &UseStateHandle<Option<WinnerSide>>,
+    on_select_winner: &Callback<WinnerSide>,
+    completion: Option<String>,
+    failed_images: &HashSet<String>,
+    on_image_error: &Callback<String>,
+) -> (Html, PointerCallbacks) {
+    match &**status {
+        FetchStatus::Loading => (
+            html! { <p>{ "Loading list..." }</p> },
+            PointerCallbacks::noop(),
+        ),
+        FetchStatus::Error(message) => (
+            html! { <p class="error">{ message }</p> },
+            PointerCallbacks::noop(),
+        ),
+        FetchStatus::Idle => {
+            let Some(list) = (**loaded).as_ref() else {
+                return (
+                    html! { <p>{ "Select a list to begin." }</p> },
+                    PointerCallbacks::noop(),
+                );
+            };
+            let transition_state = *card_transition.deref();
+            let active_drag = if matches!(transition_state, CardTransition::Idle) {
+                drag_state.deref().clone()
+            } else {
+                None
+            };
+            let (drag_delta, drag_lift) = active_drag
+                .as_ref()
+                .map(|d| d.delta())
+                .unwrap_or((0.0, 0.0));
+            let drag_lift = drag_lift.min(0.0);
+            let is_dragging = active_drag.is_some();
+            let mut style_parts: Vec<String> = Vec::new();
+            if is_dragging {
+                let background_position =
+                    50.0 + ((drag_delta / (SWIPE_THRESHOLD * 3.0)).clamp(-1.0, 1.0) * 50.0);
+                style_parts.push(format!(
+                    "transform: translate({:.1}px, {:.1}px) rotate({:.2}deg); background-position-x: {:.2}%;",
+                    drag_delta,
+                    drag_lift,
+                    drag_delta * 0.05,
+                    background_position
+                ));
+            }
+            let matchup_classes = classes!(
+                "matchup",
+                "swipe-enabled",
+                if is_dragging { Some("dragging") } else { None },
+                match transition_state {
+                    CardTransition::Exiting { side, .. } => match side {
+                        WinnerSide::Left => Some("exiting-left"),
+                        WinnerSide::Right => Some("exiting-right"),
+                        WinnerSide::Tie => Some("exiting-up"),
+                    },
+                    CardTransition::Entering { side } => match side {
+                        WinnerSide::Left => Some("entering-from-right"),
+                        WinnerSide::Right => Some("entering-from-left"),
+                        WinnerSide::Tie => Some("entering-from-below"),
+                    },
+                    CardTransition::Idle => None,
+                }
+            );
+            if let CardTransition::Exiting { side, offset } = transition_state {
+                let rotation = offset * 0.05;
+                let exit_style = match side {
+                    WinnerSide::Tie => format!(
+                        "transform: translateY({:.1}px); --start-y: {:.1}px;",
+                        offset, offset
+                    ),
+                    WinnerSide::Left | WinnerSide::Right => {
+                        let (exit_shift, rotation_shift) = if side == WinnerSide::Left {
+                            ("-150vw", "-12deg")
+                        } else {
+                            ("150vw", "12deg")
+                        };
+                        format!(
+                            "transform: translateX({:.1}px) rotate({:.2}deg); --start-x: {:.1}px; --start-rot: {:.2}deg; --exit-shift: {}; --rotation-shift: {};",
+                            offset,
+                            rotation,
+                            offset,
+                            rotation,
+                            exit_shift,
+                            rotation_shift
+                        )
+                    }
+                };
+                style_parts.push(exit_style);
+            }
+            let style = style_parts.join(" ");
+            let pointer_down = {
+                let drag_state = drag_state.clone();
+                let card_transition = card_transition.clone();
+                Callback::from(move |event: web_sys::PointerEvent| {
+                    if should_ignore_swipe(event.target()) {
+                        return;
+                    }
+                    if !matches!(*card_transition, CardTransition::Idle)
+                        || drag_state.deref().is_some()
+                    {
+                        return;
+                    }
+                    event.prevent_default();
+                    let pointer_id = event.pointer_id();
+                    if let Some(target) = event
+                        .target()
+                        .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                    {
+                        let _ = target.set_pointer_capture(pointer_id);
+                    } else if let Some(window) = web_sys::window() {
+                        if let Some(document) = window.document() {
+                            if let Some(body) = document.body() {
+                                let _ = body.set_pointer_capture(pointer_id);
+                            }
+                        }
+                    }
+
+                    drag_state.set(Some(DragState {
+                        pointer_id: event.pointer_id(),
+                        start_x: event.client_x() as f64,
+                        start_y: event.client_y() as f64,
+                        current_x: event.client_x() as f64,
+                        current_y: event.client_y() as f64,
+                    }));
+                })
+            };
+
+            let pointer_move = {
+                let drag_state = drag_state.clone();
+                let card_transition = card_transition.clone();
+                Callback::from(move |event: web_sys::PointerEvent| {
+                    if !matches!(*card_transition, CardTransition::Idle) {
+                        return;
+                    }
+                    if let Some(mut state) = drag_state.deref().clone() {
+                        if state.pointer_id == event.pointer_id() {
+                            event.prevent_default();
+                            state.current_x = event.client_x() as f64;
+                            state.current_y = event.client_y() as f64;
+                            drag_state.set(Some(state));
+                        }
+                    }
+                })
+            };
+
+            let resolve_swipe = {
+                let on_select_winner = on_select_winner.clone();
+                let card_transition = card_transition.clone();
+                let flash_side = flash_side.clone();
+                Callback::from(move |(side, offset): (WinnerSide, f64)| {
+                    if !matches!(*card_transition, CardTransition::Idle) {
+                        return;
+                    }
+                    card_transition.set(CardTransition::Exiting { side, offset });
+                    flash_side.set(Some(side));
+
+                    {
+                        let flash_side = flash_side.clone();
+                        Timeout::new(FLASH_CLEAR_DELAY_MS, move || {
+                            flash_side.set(None);
+                        })
+                        .forget();
+                    }
+
+                    {
+                        let on_select_winner = on_select_winner.clone();
+                        Timeout::new(MATCH_RESOLVE_DELAY_MS, move || {
+                            on_select_winner.emit(side);
+                        })
+                        .forget();
+                    }
+                })
+            };
+
+            let pointer_end = {
+                let drag_state = drag_state.clone();
+                let resolve_swipe = resolve_swipe.clone();
+                Callback::from(move |event: web_sys::PointerEvent| {
+                    if let Some(state) = drag_state.deref().clone() {
+                        if state.pointer_id == event.pointer_id() {
+                            if let Some(target) = event
+                                .target()
+                                .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                            {
+                                let _ = target.release_pointer_capture(event.pointer_id());
+                            }
+                            let (delta_x, delta_y) = state.delta();
+                            if -delta_y > SWIPE_THRESHOLD && -delta_y > delta_x.abs() {
+                                resolve_swipe.emit((WinnerSide::Tie, delta_y));
+                            } else if delta_x.abs() > SWIPE_THRESHOLD {
+                                let side = if delta_x > 0.0 {
+                                    WinnerSide::Right
+                                } else {
+                                    WinnerSide::Left
+                                };
+                                resolve_swipe.emit((side, delta_x));
+                            }
+                            drag_state.set(None);
+                        }
+                    }
+                })
+            };
+
+            let tie_click = {
+                let resolve_swipe = resolve_swipe.clone();
+                Callback::from(move |_: yew::MouseEvent| {
+                    resolve_swipe.emit((WinnerSide::Tie, 0.0));
+                })
+            };
+
+            let pointer_cancel = {
+                let drag_state = drag_state.clone();
+                Callback::from(move |event: web_sys::PointerEvent| {
+                    if let Some(state) = drag_state.deref().clone() {
+                        if state.pointer_id == event.pointer_id() {
+                            if let Some(target) = event
+                                .target()
+                                .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+                            {
+                                let _ = target.release_pointer_capture(event.pointer_id());
+                            }
+                            drag_state.set(None);
+                        }
+                    }
+                })
+            };
+
+            let down_for_html = pointer_down.clone();
+            let move_for_html = pointer_move.clone();
+            let end_for_html = pointer_end.clone();
+            let cancel_for_html = pointer_cancel.clone();
+
+            let matchup_panel = match (**current_match).as_ref() {
+                Some(matchup)
+                    if matchup.left_index < list.items.len()
+                        && matchup.right_index < list.items.len() =>
+                {
+                    let left_item = &list.items[matchup.left_index];
+                    let right_item = &list.items[matchup.right_index];
+
+                    html! {
+                        <div class="card-container">
+                            <div class={matchup_classes}
+                                style={style}
+                                onpointerdown={down_for_html.clone()}
+                                onpointermove={move_for_html.clone()}
+                                onpointerup={end_for_html.clone()}
+                                onpointercancel={cancel_for_html.clone()}>
+                                <div class="card left-card">
+                                    { render_card_content(left_item, failed_images, on_image_error) }
+                                    <p class="swipe-hint">{ "Swipe left" }</p>
+                                </div>
+                                <span class="vs-label">{ "vs" }</span>
+                                <div class="card right-card">
+                                    { render_card_content(right_item, failed_images, on_image_error) }
+                                    <p class="swipe-hint">{ "Swipe right" }</p>
+                                </div>
+                            </div>
+                            <button class="tie-button"
+                                data-swipe-ignore="true"
+                                onclick={tie_click}>
+                                { "Can't decide ↑" }
+                            </button>
+                        </div>
+                    }
+                }
+                _ => match completion {
+                    Some(message) => html! { <p>{ message }</p> },
+                    None => html! { <p>{ "Not enough unique items to create a matchup." }</p> },
+                },
+            };
+
+            let html_output = html! {
+                <div class="matchup-wrapper">
+                    { matchup_panel }
+                </div>
+            };
+
+            let callbacks = PointerCallbacks {
+                down: pointer_down,
+                move_cb: pointer_move,
+                up: pointer_end,
+                cancel: pointer_cancel,
+            };
+
+            (html_output, callbacks)
+        }
+    }
+}
+
+/// Reads a `?list=<url>` share link and drops it from the address bar, so a
+/// later reload opens whichever list was picked last.
+fn shared_list_from_location() -> Option<String> {
+    let window = window()?;
+    let location = window.location();
+    let params = web_sys::UrlSearchParams::new_with_str(&location.search().ok()?).ok()?;
+    let list_id = remote_list_id(&params.get("list")?)?;
+    if let (Ok(path), Ok(history)) = (location.pathname(), window.history()) {
+        let _ = history.replace_state_with_url(&JsValue::NULL, "", Some(&path));
+    }
+    Some(list_id)
+}
+
+/// A link to this page that opens the list at `url`.
+fn share_link(url: &str) -> Option<String> {
+    let location = window()?.location();
+    Some(format!(
+        "{}{}?list={}",
+        location.origin().ok()?,
+        location.pathname().ok()?,
+        String::from(js_sys::encode_uri_component(url))
+    ))
+}
+
+fn resolve_selection(lists: &[ListInfo], previous: Option<String>) -> Option<String> {
+    match previous {
+        Some(current) => {
+            if lists.iter().any(|info| info.id == current) {
+                Some(current)
+            } else {
+                lists.first().map(|info| info.id.clone())
+            }
+        }
+        None => lists.first().map(|info| info.id.clone()),
+    }
+}
+
+#[wasm_bindgen(start)]
+pub fn run_app() {
+    yew::Renderer::<App>::new().render();
+}
+
+fn should_ignore_swipe(target: Option<web_sys::EventTarget>) -> bool {
+    target
+        .and_then(|t| t.dyn_into::<Element>().ok())
+        .and_then(|element| {
+            element
+                .closest("[data-swipe-ignore=\"true\"]")
+                .ok()
+                .flatten()
+        })
+        .is_some()
+}
+
+fn swipe_background_for_delta(delta: f64, lift: f64) -> Option<SwipeBackground> {
+    if -lift > delta.abs() {
+        let strength = (-lift / SWIPE_THRESHOLD).clamp(0.0, 1.0);
+        if strength < 0.01 {
+            return None;
+        }
+        return Some(SwipeBackground {
+            start_rgb: (122, 102, 180),
+            end_rgb: (44, 30, 92),
+            start_alpha: (0.18 * strength).min(1.0),
+            end_alpha: (0.38 * strength + 0.02).min(1.0),
+        });
+    }
+
+    let normalized = (delta / SWIPE_THRESHOLD).clamp(-1.0, 1.0);
+    if normalized.abs() < 0.01 {
+        return None;
+    }
+
+    let strength = normalized.abs();
+    let start_alpha = (0.18 * strength).min(1.0);
+    let end_alpha = (0.38 * strength + 0.02).min(1.0);
+
+    if normalized < 0.0 {
+        Some(SwipeBackground {
+            start_rgb: (0, 88, 196),
+            end_rgb: (4, 21, 64),
+            start_alpha,
+            end_alpha,
+        })
+    } else {
+        Some(SwipeBackground {
+            start_rgb: (255, 62, 62),
+            end_rgb: (112, 8, 18),
+            start_alpha,
+            end_alpha,
+        })
+    }
+}
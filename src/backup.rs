@@ -0,0 +1,317 @@
+use crate::history::MatchEvent;
+use crate::storage::{decode_value, StateError, StoredAppState, StoredListState, STATE_VERSION};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashSet;
+use std::fmt;
+
+/// Marks a file as a backup of this app, so that a list file or some other
+/// JSON is not mistaken for one.
+pub const BACKUP_FORMAT: &str = "ranking-lists-backup";
+
+/// A backup is the saved state in the same versioned envelope as local
+/// storage, so older backups go through the same migrations. Custom lists and
+/// lists opened from a URL travel inside the state with their items; built-in
+/// lists are only referenced by id.
+#[derive(Serialize)]
+struct BackupFile<'a> {
+    format: &'static str,
+    version: u32,
+    /// Milliseconds since the Unix epoch.
+    exported: u64,
+    state: &'a StoredAppState,
+}
+
+#[derive(Debug)]
+pub enum BackupError {
+    NotABackup,
+    State(StateError),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::NotABackup => write!(f, "the file is not a ranking lists backup"),
+            BackupError::State(err) => err.fmt(f),
+        }
+    }
+}
+
+/// A backup file read back in.
+#[derive(Debug, Clone)]
+pub struct Backup {
+    /// When the backup was made, in milliseconds since the Unix epoch; 0 when
+    /// the file does not say.
+    pub exported: u64,
+    pub state: StoredAppState,
+}
+
+/// How an imported backup is combined with the progress already saved.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportMode {
+    /// Adds the backup's comparisons to each list's own, by item id.
+    Merge,
+    /// Discards the current progress and lists in favor of the backup.
+    Replace,
+}
+
+pub fn encode_backup(state: &StoredAppState, exported: u64) -> Result<String, serde_json::Error> {
+    serde_json::to_string_pretty(&BackupFile {
+        format: BACKUP_FORMAT,
+        version: STATE_VERSION,
+        exported,
+        state,
+    })
+}
+
+pub fn decode_backup(text: &str) -> Result<Backup, BackupError> {
+    let value: Value = serde_json::from_str(text)
+        .map_err(|err| BackupError::State(StateError::Malformed(err.to_string())))?;
+    if value.get("format").and_then(Value::as_str) != Some(BACKUP_FORMAT) {
+        return Err(BackupError::NotABackup);
+    }
+    let exported = value.get("exported").and_then(Value::as_u64).unwrap_or(0);
+    let state = decode_value(value).map_err(BackupError::State)?;
+    Ok(Backup { exported, state })
+}
+
+/// True when `backup` was made from `current` or has already been merged
+/// into it.
+pub fn is_known_backup(current: &StoredAppState, backup: &Backup) -> bool {
+    backup.exported != 0 && current.backups.contains(&backup.exported)
+}
+
+/// Applies `backup` in `mode`. Returns false, changing nothing, when asked to
+/// merge a backup that [`is_known_backup`].
+pub fn apply_backup(current: &mut StoredAppState, backup: Backup, mode: ImportMode) -> bool {
+    match mode {
+        ImportMode::Replace => *current = backup.state,
+        ImportMode::Merge => {
+            if is_known_backup(current, &backup) {
+                return false;
+            }
+            merge_backup(current, backup.state);
+        }
+    }
+    if backup.exported != 0 {
+        note_backup(current, backup.exported);
+    }
+    true
+}
+
+/// Remembers a backup made from or merged into `state`.
+pub fn note_backup(state: &mut StoredAppState, exported: u64) {
+    if !state.backups.contains(&exported) {
+        state.backups.push(exported);
+    }
+}
+
+/// Lists only in the backup are added as they are; custom and cached lists
+/// already here keep their current definition.
+fn merge_backup(current: &mut StoredAppState, backup: StoredAppState) {
+    for exported in backup.backups {
+        note_backup(current, exported);
+    }
+    for list in backup.custom_lists {
+        if !current.custom_lists.iter().any(|known| known.id == list.id) {
+            current.custom_lists.push(list);
+        }
+    }
+    for list in backup.cached_lists {
+        if !current.cached_lists.iter().any(|known| known.id == list.id) {
+            current.cached_lists.push(list);
+        }
+    }
+    for (id, incoming) in backup.lists {
+        match current.lists.get_mut(&id) {
+            Some(existing) => merge_list(existing, incoming),
+            None => {
+                current.lists.insert(id, incoming);
+            }
+        }
+    }
+}
+
+/// Appends the backup's events, keeping the log in time order. An event with
+/// the same time, session and outcome as one already here is the same
+/// decision and is skipped. Events without a time, such as those migrated
+/// from counts, cannot be told apart and are all added; [`apply_backup`]
+/// keeps them from being added twice by not merging a backup it knows.
+fn merge_list(existing: &mut StoredListState, incoming: StoredListState) {
+    let known: HashSet<(u64, String, OutcomeKey)> = existing
+        .events
+        .iter()
+        .filter(|event| event.at != 0)
+        .map(|event| (event.at, event.session.clone(), outcome_key(event)))
+        .collect();
+    for event in incoming.events {
+        // Copies from the lists a composite is built from come back when it
+        // inherits from the merged sources.
+        let inherited = event
+            .source
+            .as_ref()
+            .is_some_and(|source| existing.inherited.contains_key(source));
+        if inherited {
+            continue;
+        }
+        let duplicate = event.at != 0
+            && known.contains(&(event.at, event.session.clone(), outcome_key(&event)));
+        if !duplicate {
+            existing.events.push(event);
+        }
+    }
+    existing.events.sort_by_key(|event| event.at);
+    existing.rebuild_from_events();
+}
+
+/// `(winner, loser, tie, source)`, with tied items in sorted order since
+/// either may have been shown on the left.
+type OutcomeKey = (String, String, bool, Option<String>);
+
+fn outcome_key(event: &MatchEvent) -> OutcomeKey {
+    let (first, second) = if event.tie && event.loser < event.winner {
+        (&event.loser, &event.winner)
+    } else {
+        (&event.winner, &event.loser)
+    };
+    (
+        first.clone(),
+        second.clone(),
+        event.tie,
+        event.source.clone(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::CustomList;
+    use crate::history::EventStamp;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    fn decided(item_ids: &[&str], outcomes: &[(&str, &str, u64)]) -> StoredListState {
+        let mut list = StoredListState::new(&ids(item_ids));
+        for &(winner, loser, at) in outcomes {
+            let stamp = EventStamp {
+                at,
+                session: "s1".to_string(),
+            };
+            list.events
+                .push(MatchEvent::new(winner, loser, Some(winner)).stamped(&stamp));
+        }
+        list.replay();
+        list
+    }
+
+    #[test]
+    fn backups_round_trip_and_are_recognized() {
+        let mut state = StoredAppState::default();
+        state.custom_lists.push(CustomList {
+            id: "custom-pets".to_string(),
+            title: "Pets".to_string(),
+            ..CustomList::default()
+        });
+        state.lists.insert(
+            "pets".to_string(),
+            decided(&["cat", "dog"], &[("dog", "cat", 5)]),
+        );
+
+        let text = encode_backup(&state, 1_700_000_000_000).unwrap();
+        let restored = decode_backup(&text).unwrap();
+        assert_eq!(restored.exported, 1_700_000_000_000);
+        assert_eq!(restored.state.custom_lists, state.custom_lists);
+        assert_eq!(restored.state.lists["pets"].win_matrix[1][0], 1);
+
+        assert!(matches!(
+            decode_backup(r#"{ "version": 2, "state": {} }"#),
+            Err(BackupError::NotABackup)
+        ));
+        assert!(matches!(
+            decode_backup("not json"),
+            Err(BackupError::State(StateError::Malformed(_)))
+        ));
+    }
+
+    #[test]
+    fn merging_sums_counts_by_item_id() {
+        let mut current = StoredAppState::default();
+        current.lists.insert(
+            "pets".to_string(),
+            decided(&["cat", "dog"], &[("dog", "cat", 1)]),
+        );
+        let mut backup = StoredAppState::default();
+        // Same items in another order, one decision already here and one new.
+        backup.lists.insert(
+            "pets".to_string(),
+            decided(&["dog", "cat"], &[("dog", "cat", 1), ("cat", "dog", 2)]),
+        );
+        backup
+            .lists
+            .insert("fish".to_string(), decided(&["carp", "pike"], &[]));
+
+        let backup = Backup {
+            exported: 1,
+            state: backup,
+        };
+        let mut merged = current.clone();
+        assert!(apply_backup(&mut merged, backup.clone(), ImportMode::Merge));
+        let pets = &merged.lists["pets"];
+        assert_eq!(pets.item_ids, ids(&["cat", "dog"]));
+        assert_eq!(pets.win_matrix, [[0, 1], [1, 0]]);
+        assert_eq!(pets.events.len(), 2);
+        assert!(merged.lists.contains_key("fish"));
+
+        apply_backup(&mut current, backup, ImportMode::Replace);
+        assert_eq!(current.lists["pets"].item_ids, ids(&["dog", "cat"]));
+        assert_eq!(current.backups, [1]);
+    }
+
+    #[test]
+    fn merging_migrated_progress_twice_counts_it_once() {
+        // Outcomes migrated from counts carry no time or session.
+        let untimed = |item_ids: &[&str], outcomes: &[(&str, &str)]| {
+            let mut list = StoredListState::new(&ids(item_ids));
+            for &(winner, loser) in outcomes {
+                list.events
+                    .push(MatchEvent::new(winner, loser, Some(winner)));
+            }
+            list.events.push(MatchEvent::new("cat", "dog", None));
+            list.replay();
+            list
+        };
+        let mut current = StoredAppState::default();
+        current.lists.insert(
+            "pets".to_string(),
+            untimed(&["cat", "dog"], &[("dog", "cat")]),
+        );
+        let mut backup = StoredAppState::default();
+        backup.lists.insert(
+            "pets".to_string(),
+            untimed(&["dog", "cat"], &[("dog", "cat"), ("dog", "cat")]),
+        );
+
+        let backup = Backup {
+            exported: 1_700_000_000_000,
+            state: backup,
+        };
+
+        // Untimed outcomes are added up: one win and a tie here, two wins and
+        // a tie in the backup.
+        assert!(apply_backup(
+            &mut current,
+            backup.clone(),
+            ImportMode::Merge
+        ));
+        let merged = current.lists["pets"].clone();
+        assert_eq!(merged.win_matrix, [[0, 0], [3, 0]]);
+        assert_eq!(merged.tie_matrix, [[0, 2], [2, 0]]);
+        assert_eq!(merged.events.len(), 5);
+
+        assert!(is_known_backup(&current, &backup));
+        assert!(!apply_backup(&mut current, backup, ImportMode::Merge));
+        assert_eq!(current.lists["pets"].events, merged.events);
+    }
+}
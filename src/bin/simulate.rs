@@ -0,0 +1,449 @@
+//! Offline comparison of matchup strategies.
+//!
+//! Generates synthetic lists with known strengths, lets a noisy simulated voter
+//! answer every matchup and reports how well the Bradley-Terry ranking agrees
+//! with the ground truth after a given number of comparisons.
+//!
+//! ```text
+//! cargo run --release --bin simulate -- --items 40 --comparisons 400 --format json
+//! ```
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::Serialize;
+use std::env;
+use std::process;
+use yew_project::history::EventStamp;
+use yew_project::matchflow::{Matchup, MatchupContext, MatchupMode};
+use yew_project::ranking::{BradleyTerry, RatingEngine};
+use yew_project::storage::StoredListState;
+
+const HISTORY_LEN: usize = 8;
+
+const USAGE: &str = "\
+Usage: simulate [options]
+
+Options:
+  --items N          items per synthetic list (default 30)
+  --comparisons N    comparisons per run (default 300)
+  --every N          report every N comparisons (default 50)
+  --runs N           lists per strategy, averaged (default 10)
+  --noise X          voter temperature on the log-odds scale (default 0.5)
+  --lapse P          chance that a voter answers at random (default 0.05)
+  --tie-rate P       chance that a voter skips with a tie (default 0)
+  --seed N           base seed (default 1)
+  --strategies LIST  comma-separated: adaptive,exact_order,top_k,information_gain
+  --format FORMAT    csv or json (default csv)";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+struct Config {
+    items: usize,
+    comparisons: usize,
+    every: usize,
+    runs: usize,
+    noise: f64,
+    lapse: f64,
+    tie_rate: f64,
+    seed: u64,
+    strategies: Vec<MatchupMode>,
+    format: OutputFormat,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            items: 30,
+            comparisons: 300,
+            every: 50,
+            runs: 10,
+            noise: 0.5,
+            lapse: 0.05,
+            tie_rate: 0.0,
+            seed: 1,
+            strategies: MatchupMode::ALL.to_vec(),
+            format: OutputFormat::Csv,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct Row {
+    strategy: &'static str,
+    comparisons: usize,
+    runs: usize,
+    kendall_tau: f64,
+    spearman: f64,
+    finished_runs: usize,
+}
+
+/// A voter who prefers the stronger item with logistic noise, and sometimes
+/// does not pay attention at all.
+struct Voter {
+    strengths: Vec<f64>,
+    noise: f64,
+    lapse: f64,
+    tie_rate: f64,
+}
+
+impl Voter {
+    fn judge(&self, matchup: &Matchup, rng: &mut impl Rng) -> Option<usize> {
+        let (left, right) = (matchup.left_index, matchup.right_index);
+        if rng.gen_bool(self.tie_rate) {
+            return None;
+        }
+        let p_left = if rng.gen_bool(self.lapse) {
+            0.5
+        } else {
+            let difference = (self.strengths[left] - self.strengths[right]) / self.noise;
+            1.0 / (1.0 + (-difference).exp())
+        };
+        Some(if rng.gen_bool(p_left) { left } else { right })
+    }
+}
+
+fn main() {
+    let config = match parse_args(env::args().skip(1)) {
+        Ok(config) => config,
+        Err(message) => {
+            eprintln!("{message}\n\n{USAGE}");
+            process::exit(2);
+        }
+    };
+
+    let rows = simulate(&config);
+    match config.format {
+        OutputFormat::Csv => {
+            println!("strategy,comparisons,runs,kendall_tau,spearman,finished_runs");
+            for row in &rows {
+                println!(
+                    "{},{},{},{:.4},{:.4},{}",
+                    row.strategy,
+                    row.comparisons,
+                    row.runs,
+                    row.kendall_tau,
+                    row.spearman,
+                    row.finished_runs
+                );
+            }
+        }
+        OutputFormat::Json => match serde_json::to_string_pretty(&rows) {
+            Ok(json) => println!("{json}"),
+            Err(err) => {
+                eprintln!("Failed to serialize results: {err}");
+                process::exit(1);
+            }
+        },
+    }
+}
+
+fn parse_args(args: impl Iterator<Item = String>) -> Result<Config, String> {
+    let mut config = Config::default();
+    let mut args = args.peekable();
+    while let Some(flag) = args.next() {
+        if flag == "--help" || flag == "-h" {
+            println!("{USAGE}");
+            process::exit(0);
+        }
+        let value = args
+            .next()
+            .ok_or_else(|| format!("Missing value for {flag}"))?;
+        let number = |value: &str| {
+            value
+                .parse::<f64>()
+                .map_err(|_| format!("Invalid number for {flag}: {value}"))
+        };
+        let count = |value: &str| {
+            value
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid count for {flag}: {value}"))
+        };
+        match flag.as_str() {
+            "--items" => config.items = count(&value)?,
+            "--comparisons" => config.comparisons = count(&value)?,
+            "--every" => config.every = count(&value)?.max(1),
+            "--runs" => config.runs = count(&value)?.max(1),
+            "--noise" => config.noise = number(&value)?,
+            "--lapse" => config.lapse = number(&value)?,
+            "--tie-rate" => config.tie_rate = number(&value)?,
+            "--seed" => {
+                config.seed = value
+                    .parse()
+                    .map_err(|_| format!("Invalid seed: {value}"))?
+            }
+            "--strategies" => {
+                config.strategies = value
+                    .split(',')
+                    .map(|name| parse_strategy(name.trim()))
+                    .collect::<Result<_, _>>()?
+            }
+            "--format" => {
+                config.format = match value.as_str() {
+                    "csv" => OutputFormat::Csv,
+                    "json" => OutputFormat::Json,
+                    other => return Err(format!("Unknown format: {other}")),
+                }
+            }
+            other => return Err(format!("Unknown option: {other}")),
+        }
+    }
+
+    if config.items < 2 {
+        return Err("--items must be at least 2".to_string());
+    }
+    if config.noise <= 0.0 {
+        return Err("--noise must be positive".to_string());
+    }
+    for (name, value) in [("--lapse", config.lapse), ("--tie-rate", config.tie_rate)] {
+        if !(0.0..=1.0).contains(&value) {
+            return Err(format!("{name} must be between 0 and 1"));
+        }
+    }
+    Ok(config)
+}
+
+fn parse_strategy(name: &str) -> Result<MatchupMode, String> {
+    MatchupMode::ALL
+        .into_iter()
+        .find(|mode| strategy_key(*mode) == name)
+        .ok_or_else(|| format!("Unknown strategy: {name}"))
+}
+
+fn strategy_key(mode: MatchupMode) -> &'static str {
+    match mode {
+        MatchupMode::Adaptive => "adaptive",
+        MatchupMode::ExactOrder => "exact_order",
+        MatchupMode::TopK => "top_k",
+        MatchupMode::InformationGain => "information_gain",
+    }
+}
+
+fn simulate(config: &Config) -> Vec<Row> {
+    let checkpoints: Vec<usize> = (1..=config.comparisons / config.every)
+        .map(|step| step * config.every)
+        .collect();
+
+    let mut rows = Vec::new();
+    for &mode in &config.strategies {
+        let mut tau_sums = vec![0.0; checkpoints.len()];
+        let mut spearman_sums = vec![0.0; checkpoints.len()];
+        let mut finished = vec![0; checkpoints.len()];
+
+        for run in 0..config.runs {
+            // Every strategy sees the same lists, so the columns are comparable.
+            let run_seed = config.seed.wrapping_add(run as u64);
+            let strengths = synthetic_strengths(config.items, run_seed);
+            let voter = Voter {
+                strengths,
+                noise: config.noise,
+                lapse: config.lapse,
+                tie_rate: config.tie_rate,
+            };
+            let results = run_strategy(mode, &voter, &checkpoints, run_seed);
+            for (index, (tau, rho, done)) in results.into_iter().enumerate() {
+                tau_sums[index] += tau;
+                spearman_sums[index] += rho;
+                if done {
+                    finished[index] += 1;
+                }
+            }
+        }
+
+        let runs = config.runs as f64;
+        for (index, &comparisons) in checkpoints.iter().enumerate() {
+            rows.push(Row {
+                strategy: strategy_key(mode),
+                comparisons,
+                runs: config.runs,
+                kendall_tau: tau_sums[index] / runs,
+                spearman: spearman_sums[index] / runs,
+                finished_runs: finished[index],
+            });
+        }
+    }
+    rows
+}
+
+/// Runs one list to the last checkpoint and returns `(tau, rho, finished)` at
+/// each checkpoint. A strategy that runs out of matchups keeps its last ranking.
+fn run_strategy(
+    mode: MatchupMode,
+    voter: &Voter,
+    checkpoints: &[usize],
+    seed: u64,
+) -> Vec<(f64, f64, bool)> {
+    let count = voter.strengths.len();
+    let item_ids: Vec<String> = (0..count).map(|index| format!("item-{index}")).collect();
+    let mut state = StoredListState::new(&item_ids);
+    state.set_mode(mode);
+    let mut engine: Box<dyn RatingEngine> = Box::new(BradleyTerry::new(count));
+    state.store_engine(engine.as_ref());
+
+    let mut strategy_rng = StdRng::seed_from_u64(seed ^ 0x5eed_0001);
+    let mut voter_rng = StdRng::seed_from_u64(seed ^ 0x5eed_0002);
+    let mut history: Vec<Matchup> = Vec::new();
+    let mut finished = false;
+    let mut results = Vec::with_capacity(checkpoints.len());
+    let mut done = 0;
+
+    for &checkpoint in checkpoints {
+        while !finished && done < checkpoint {
+            let matchup = state.strategy().and_then(|strategy| {
+                let context = MatchupContext::new(
+                    engine.as_ref(),
+                    &state.win_matrix,
+                    &state.tie_matrix,
+                    &state.match_totals,
+                );
+                strategy.next_matchup(&context, &history, &mut strategy_rng)
+            });
+            let Some(matchup) = matchup else {
+                finished = true;
+                break;
+            };
+
+            let winner = voter.judge(&matchup, &mut voter_rng);
+            state.record_result(
+                matchup.left_index,
+                matchup.right_index,
+                winner,
+                engine.as_mut(),
+                &EventStamp::default(),
+            );
+            history.push(matchup);
+            if history.len() > HISTORY_LEN {
+                history.remove(0);
+            }
+            done += 1;
+        }
+
+        // Swipes only warm-start the fit; score the converged model, on a copy
+        // so the checkpoints do not steer the rest of the run.
+        let mut fitted = engine.clone();
+        fitted.converge(&state.win_matrix, &state.tie_matrix);
+        let estimate = fitted.abilities();
+        results.push((
+            kendall_tau(&voter.strengths, estimate),
+            spearman(&voter.strengths, estimate),
+            finished,
+        ));
+    }
+    results
+}
+
+/// Standard-normal strengths via Box-Muller.
+fn synthetic_strengths(count: usize, seed: u64) -> Vec<f64> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..count)
+        .map(|_| {
+            let u1: f64 = rng.gen_range(f64::EPSILON..1.0);
+            let u2: f64 = rng.gen();
+            (-2.0 * u1.ln()).sqrt() * (2.0 * std::f64::consts::PI * u2).cos()
+        })
+        .collect()
+}
+
+/// Kendall's tau-a between two score vectors.
+fn kendall_tau(truth: &[f64], estimate: &[f64]) -> f64 {
+    let count = truth.len().min(estimate.len());
+    if count < 2 {
+        return 0.0;
+    }
+    let mut balance = 0.0;
+    for i in 0..count {
+        for j in (i + 1)..count {
+            let product = (truth[i] - truth[j]) * (estimate[i] - estimate[j]);
+            if product > 0.0 {
+                balance += 1.0;
+            } else if product < 0.0 {
+                balance -= 1.0;
+            }
+        }
+    }
+    balance / (count * (count - 1) / 2) as f64
+}
+
+/// Spearman's rho: Pearson correlation of the (average) ranks.
+fn spearman(truth: &[f64], estimate: &[f64]) -> f64 {
+    let count = truth.len().min(estimate.len());
+    if count < 2 {
+        return 0.0;
+    }
+    let truth_ranks = ranks(&truth[..count]);
+    let estimate_ranks = ranks(&estimate[..count]);
+    let mean = (count as f64 - 1.0) / 2.0;
+    let mut covariance = 0.0;
+    let mut truth_variance = 0.0;
+    let mut estimate_variance = 0.0;
+    for (a, b) in truth_ranks.iter().zip(&estimate_ranks) {
+        covariance += (a - mean) * (b - mean);
+        truth_variance += (a - mean).powi(2);
+        estimate_variance += (b - mean).powi(2);
+    }
+    let denominator = (truth_variance * estimate_variance).sqrt();
+    if denominator > 0.0 {
+        covariance / denominator
+    } else {
+        0.0
+    }
+}
+
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut order: Vec<usize> = (0..values.len()).collect();
+    order.sort_by(|&a, &b| values[a].total_cmp(&values[b]));
+    let mut ranks = vec![0.0; values.len()];
+    let mut start = 0;
+    while start < order.len() {
+        let mut end = start + 1;
+        while end < order.len() && values[order[end]] == values[order[start]] {
+            end += 1;
+        }
+        let average = (start + end - 1) as f64 / 2.0;
+        for &index in &order[start..end] {
+            ranks[index] = average;
+        }
+        start = end;
+    }
+    ranks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn correlations_detect_agreement_and_reversal() {
+        let truth = [3.0, 1.0, 2.0, 0.0];
+        let reversed: Vec<f64> = truth.iter().map(|value| -value).collect();
+
+        assert_eq!(kendall_tau(&truth, &truth), 1.0);
+        assert_eq!(kendall_tau(&truth, &reversed), -1.0);
+        assert!((spearman(&truth, &truth) - 1.0).abs() < 1e-12);
+        assert!((spearman(&truth, &reversed) + 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn ties_share_their_average_rank() {
+        assert_eq!(ranks(&[2.0, 1.0, 2.0]), vec![1.5, 0.0, 1.5]);
+    }
+
+    #[test]
+    fn exact_order_recovers_a_noiseless_ranking() {
+        let voter = Voter {
+            strengths: synthetic_strengths(12, 7),
+            noise: 1e-3,
+            lapse: 0.0,
+            tie_rate: 0.0,
+        };
+        let results = run_strategy(MatchupMode::ExactOrder, &voter, &[200], 7);
+        let (tau, _, finished) = results[0];
+
+        assert!(finished);
+        assert!(tau > 0.9);
+    }
+}
@@ -1,23 +1,525 @@
+#[cfg(feature = "web")]
 use gloo_net::http::Request;
-use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+#[cfg(feature = "web")]
+use std::future::Future;
+#[cfg(feature = "web")]
+use std::pin::Pin;
+use unicode_normalization::UnicodeNormalization;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ListInfo {
     pub id: String,
     pub label: String,
+    pub description: Option<String>,
+    pub author: Option<String>,
+    pub version: Option<String>,
+    pub locale: Option<String>,
+    /// Group the list is shown under in the picker.
+    pub category: Option<String>,
+    pub item_count: Option<usize>,
+    /// Picture for the picker, resolved like an item's `image`.
+    pub thumbnail: Option<String>,
+    /// Last change as an ISO `YYYY-MM-DD` date.
+    pub updated: Option<String>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+impl ListInfo {
+    /// True when every word of `query` appears in the title, description,
+    /// category or id, ignoring case.
+    pub fn matches_search(&self, query: &str) -> bool {
+        let haystack = [
+            Some(&self.label),
+            self.description.as_ref(),
+            self.category.as_ref(),
+            Some(&self.id),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|text| text.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+        query
+            .split_whitespace()
+            .all(|word| haystack.contains(&word.to_lowercase()))
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct ListItem {
     pub id: String,
     pub label: String,
+    pub subtitle: Option<String>,
+    pub tags: Vec<String>,
+    pub image: Option<String>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct LoadedList {
     pub info: ListInfo,
     pub items: Vec<ListItem>,
+    /// Lists this one was composed from; empty for an ordinary list.
+    pub sources: Vec<String>,
+}
+
+/// How a composite list combines other lists. Items are matched by id, and
+/// every result holds each id and each label once.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListExpr {
+    List(String),
+    /// Items of every operand, in order of first appearance.
+    Union(Vec<ListExpr>),
+    /// Items of the first operand that every other operand also has.
+    Intersection(Vec<ListExpr>),
+    /// Items of the first operand that no other operand has.
+    Difference(Vec<ListExpr>),
+}
+
+impl ListExpr {
+    /// Ids of the lists the expression reads, each once, in order.
+    pub fn sources(&self) -> Vec<String> {
+        let mut sources = Vec::new();
+        self.collect_sources(&mut sources);
+        sources
+    }
+
+    fn collect_sources(&self, sources: &mut Vec<String>) {
+        match self {
+            ListExpr::List(id) => {
+                if !sources.contains(id) {
+                    sources.push(id.clone());
+                }
+            }
+            ListExpr::Union(operands)
+            | ListExpr::Intersection(operands)
+            | ListExpr::Difference(operands) => {
+                for operand in operands {
+                    operand.collect_sources(sources);
+                }
+            }
+        }
+    }
+
+    fn evaluate(&self, lists: &HashMap<String, LoadedList>) -> Result<Vec<ListItem>, DataError> {
+        let operands = match self {
+            ListExpr::List(id) => {
+                let list = lists
+                    .get(id)
+                    .ok_or_else(|| DataError::NotFound(id.clone()))?;
+                return Ok(dedupe_items(list.items.iter().cloned()));
+            }
+            ListExpr::Union(operands)
+            | ListExpr::Intersection(operands)
+            | ListExpr::Difference(operands) => operands
+                .iter()
+                .map(|operand| operand.evaluate(lists))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        let mut operands = operands.into_iter();
+        let first = operands.next().unwrap_or_default();
+        let ids = |items: &[ListItem]| -> HashSet<String> {
+            items.iter().map(|item| item.id.clone()).collect()
+        };
+
+        Ok(match self {
+            ListExpr::List(_) => unreachable!(),
+            ListExpr::Union(_) => dedupe_items(first.into_iter().chain(operands.flatten())),
+            ListExpr::Intersection(_) => {
+                let others: Vec<HashSet<String>> = operands.map(|items| ids(&items)).collect();
+                first
+                    .into_iter()
+                    .filter(|item| others.iter().all(|other| other.contains(&item.id)))
+                    .collect()
+            }
+            ListExpr::Difference(_) => {
+                let excluded: HashSet<String> = operands.flat_map(|items| ids(&items)).collect();
+                first
+                    .into_iter()
+                    .filter(|item| !excluded.contains(&item.id))
+                    .collect()
+            }
+        })
+    }
+}
+
+/// Keeps the first item for each id and for each label, compared without
+/// regard to case or Unicode normalization, so a name a source lists twice
+/// (as `nikolas` and `nikolas-2`) is only ranked once. Tags of later copies
+/// are added to the kept item.
+fn dedupe_items(items: impl IntoIterator<Item = ListItem>) -> Vec<ListItem> {
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    let mut label_positions: HashMap<String, usize> = HashMap::new();
+    let mut unique: Vec<ListItem> = Vec::new();
+    for item in items {
+        let label = label_key(&item.label);
+        let kept = positions
+            .get(&item.id)
+            .or_else(|| label_positions.get(&label))
+            .copied();
+        match kept {
+            Some(position) => {
+                let kept = &mut unique[position];
+                for tag in item.tags {
+                    if !kept.tags.contains(&tag) {
+                        kept.tags.push(tag);
+                    }
+                }
+            }
+            None => {
+                positions.insert(item.id.clone(), unique.len());
+                label_positions.insert(label, unique.len());
+                unique.push(item);
+            }
+        }
+    }
+    unique
+}
+
+fn label_key(label: &str) -> String {
+    label
+        .nfc()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A list file whose items come from other lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompositeList {
+    pub info: ListInfo,
+    pub expr: ListExpr,
+}
+
+impl CompositeList {
+    pub fn sources(&self) -> Vec<String> {
+        self.expr.sources()
+    }
+
+    /// Combines the loaded `lists`, which must include every source.
+    pub fn build(&self, lists: &HashMap<String, LoadedList>) -> Result<LoadedList, DataError> {
+        let items = self.expr.evaluate(lists)?;
+        if items.is_empty() {
+            return Err(DataError::Parse(format!(
+                "List '{}' does not contain any items",
+                self.info.id
+            )));
+        }
+        Ok(LoadedList {
+            info: self.info.clone(),
+            items,
+            sources: self.sources(),
+        })
+    }
+}
+
+/// A parsed list file: either its own items or a recipe over other lists.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ListFile {
+    Items(LoadedList),
+    Composite(CompositeList),
+}
+
+/// Prefix that keeps ids of lists made in the app apart from bundled files.
+pub const CUSTOM_LIST_PREFIX: &str = "custom:";
+
+/// A list created in the app. Items keep their ids when renamed or moved, so
+/// saved progress follows them.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomList {
+    pub id: String,
+    pub title: String,
+    #[serde(default)]
+    pub items: Vec<CustomItem>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomItem {
+    /// Empty until the list is first saved.
+    #[serde(default)]
+    pub id: String,
+    pub label: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl CustomList {
+    /// An empty list with an id derived from `title` that is not in `taken`.
+    pub fn create<'a>(title: &str, taken: impl IntoIterator<Item = &'a str>) -> Self {
+        let mut seen: HashSet<String> = taken.into_iter().map(str::to_string).collect();
+        let slug = slugify(title);
+        let base = format!(
+            "{}{}",
+            CUSTOM_LIST_PREFIX,
+            if slug.is_empty() { "list" } else { &slug }
+        );
+        Self {
+            id: ensure_unique_id(&mut seen, base),
+            title: title.trim().to_string(),
+            items: Vec::new(),
+        }
+    }
+
+    /// A draft holding the items of an imported list. The id is assigned on
+    /// the first save, like any other new list.
+    pub fn from_loaded(list: &LoadedList) -> Self {
+        Self {
+            id: String::new(),
+            title: list.info.label.clone(),
+            items: list
+                .items
+                .iter()
+                .map(|item| CustomItem {
+                    id: item.id.clone(),
+                    label: item.label.clone(),
+                    tags: item.tags.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    pub fn info(&self) -> ListInfo {
+        ListInfo {
+            id: self.id.clone(),
+            label: self.title.clone(),
+            item_count: Some(self.items.len()),
+            ..ListInfo::default()
+        }
+    }
+
+    /// Validates the list exactly like a bundled file, then stores the ids
+    /// that new items were given and the trimmed labels.
+    pub fn finalize(&mut self) -> Result<LoadedList, DataError> {
+        if self.title.trim().is_empty() {
+            return Err(DataError::Parse("The list needs a title".to_string()));
+        }
+        let list = self.to_loaded()?;
+        self.title = list.info.label.clone();
+        self.items = list
+            .items
+            .iter()
+            .map(|item| CustomItem {
+                id: item.id.clone(),
+                label: item.label.clone(),
+                tags: item.tags.clone(),
+            })
+            .collect();
+        Ok(list)
+    }
+
+    pub fn to_loaded(&self) -> Result<LoadedList, DataError> {
+        let items = self
+            .items
+            .iter()
+            .map(|item| ListItem {
+                id: item.id.clone(),
+                label: item.label.clone(),
+                tags: item.tags.clone(),
+                ..ListItem::default()
+            })
+            .collect();
+        list_from_items(&self.id, Some(self.title.clone()), items)
+    }
+}
+
+pub fn is_custom_list_id(id: &str) -> bool {
+    id.starts_with(CUSTOM_LIST_PREFIX)
+}
+
+/// Prefix for lists loaded from a URL; the rest of the id is the URL itself,
+/// so a shared link always maps to the same saved progress.
+pub const REMOTE_LIST_PREFIX: &str = "url:";
+
+/// The list id for `url`, if it is an absolute http(s) URL.
+pub fn remote_list_id(url: &str) -> Option<String> {
+    let url = url.trim();
+    let rest = url
+        .strip_prefix("https://")
+        .or_else(|| url.strip_prefix("http://"))?;
+    let host = rest.split(['/', '?', '#']).next().unwrap_or("");
+    if host.is_empty() || url.contains(char::is_whitespace) {
+        return None;
+    }
+    Some(format!("{}{}", REMOTE_LIST_PREFIX, url))
+}
+
+pub fn remote_list_url(list_id: &str) -> Option<&str> {
+    list_id.strip_prefix(REMOTE_LIST_PREFIX)
+}
+
+#[derive(Debug, Deserialize)]
+struct RawList {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+    #[serde(default)]
+    author: Option<String>,
+    #[serde(default)]
+    version: Option<RawVersion>,
+    #[serde(default)]
+    locale: Option<String>,
+    #[serde(default)]
+    items: Vec<RawItem>,
+    #[serde(default)]
+    compose: Option<RawExpr>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawExpr {
+    List(String),
+    Op(RawOp),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum RawOp {
+    Union(Vec<RawExpr>),
+    Intersection(Vec<RawExpr>),
+    Difference(Vec<RawExpr>),
+}
+
+impl RawExpr {
+    fn into_expr(self, list_id: &str) -> Result<ListExpr, DataError> {
+        let operands = |operands: Vec<RawExpr>| -> Result<Vec<ListExpr>, DataError> {
+            if operands.is_empty() {
+                return Err(DataError::Parse(format!(
+                    "An operation in list '{}' has no lists to combine",
+                    list_id
+                )));
+            }
+            operands
+                .into_iter()
+                .map(|operand| operand.into_expr(list_id))
+                .collect()
+        };
+        Ok(match self {
+            // Operands may name a bundled list or link to one elsewhere.
+            RawExpr::List(source) => {
+                let source = source.trim();
+                ListExpr::List(remote_list_id(source).unwrap_or_else(|| source.to_string()))
+            }
+            RawExpr::Op(RawOp::Union(list)) => ListExpr::Union(operands(list)?),
+            RawExpr::Op(RawOp::Intersection(list)) => ListExpr::Intersection(operands(list)?),
+            RawExpr::Op(RawOp::Difference(list)) => ListExpr::Difference(operands(list)?),
+        })
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawIndex {
+    Lists(Vec<RawIndexEntry>),
+    Index {
+        lists: Vec<RawIndexEntry>,
+        #[serde(default)]
+        categories: Vec<String>,
+    },
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawIndexEntry {
+    Id(String),
+    List {
+        id: String,
+        #[serde(default)]
+        title: Option<String>,
+        #[serde(default)]
+        description: Option<String>,
+        #[serde(default)]
+        category: Option<String>,
+        #[serde(default)]
+        count: Option<usize>,
+        #[serde(default)]
+        thumbnail: Option<String>,
+        #[serde(default)]
+        updated: Option<String>,
+    },
+}
+
+impl RawIndexEntry {
+    fn into_info(self) -> Result<ListInfo, DataError> {
+        let (id, title, description, category, count, thumbnail, updated) = match self {
+            RawIndexEntry::Id(id) => (id, None, None, None, None, None, None),
+            RawIndexEntry::List {
+                id,
+                title,
+                description,
+                category,
+                count,
+                thumbnail,
+                updated,
+            } => (id, title, description, category, count, thumbnail, updated),
+        };
+        let id = id.trim().to_string();
+        if id.is_empty() {
+            return Err(DataError::Parse(
+                "The index has a list without an id".to_string(),
+            ));
+        }
+        let updated = non_empty(updated);
+        if let Some(date) = updated.as_deref().filter(|date| !is_iso_date(date)) {
+            return Err(DataError::Parse(format!(
+                "List '{}' has an invalid date '{}'; use YYYY-MM-DD",
+                id, date
+            )));
+        }
+        Ok(ListInfo {
+            label: non_empty(title).unwrap_or_else(|| default_title(&id)),
+            description: non_empty(description),
+            category: non_empty(category),
+            item_count: count,
+            thumbnail: non_empty(thumbnail),
+            updated,
+            id,
+            ..ListInfo::default()
+        })
+    }
+}
+
+fn is_iso_date(text: &str) -> bool {
+    let parts: Vec<&str> = text.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    let number = |part: &str, len: usize| {
+        (part.len() == len && part.chars().all(|ch| ch.is_ascii_digit()))
+            .then(|| part.parse::<u32>().ok())
+            .flatten()
+    };
+    number(year, 4).is_some()
+        && number(month, 2).is_some_and(|month| (1..=12).contains(&month))
+        && number(day, 2).is_some_and(|day| (1..=31).contains(&day))
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawVersion {
+    Text(String),
+    Number(serde_json::Number),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum RawItem {
+    Label(String),
+    Item {
+        #[serde(default)]
+        id: Option<String>,
+        label: String,
+        #[serde(default)]
+        subtitle: Option<String>,
+        #[serde(default)]
+        tags: Vec<String>,
+        #[serde(default)]
+        image: Option<String>,
+    },
 }
 
 #[derive(Debug)]
@@ -28,6 +530,7 @@ pub enum DataError {
 }
 
 impl DataError {
+    #[cfg(feature = "web")]
     fn network<E: std::fmt::Display>(err: E) -> Self {
         Self::Network(err.to_string())
     }
@@ -47,6 +550,7 @@ impl fmt::Display for DataError {
     }
 }
 
+#[cfg(feature = "web")]
 pub async fn fetch_available_lists() -> Result<Vec<ListInfo>, DataError> {
     let response = Request::get("assets/index.json")
         .send()
@@ -61,28 +565,73 @@ pub async fn fetch_available_lists() -> Result<Vec<ListInfo>, DataError> {
     }
 
     let text = response.text().await.map_err(DataError::network)?;
-    let ids: Vec<String> = serde_json::from_str(&text).map_err(DataError::parse)?;
+    parse_list_index(&text)
+}
 
-    let infos = ids
-        .into_iter()
-        .map(|id| ListInfo {
-            label: display_name(&id),
-            id,
-        })
-        .collect();
+#[cfg(feature = "web")]
+pub async fn load_list(list_id: &str) -> Result<LoadedList, DataError> {
+    let text = fetch_list_text(list_id).await?;
+    load_list_from_text(list_id, text).await
+}
 
-    Ok(infos)
+/// Parses a fetched list file and, for a composite list, loads the lists it
+/// is built from.
+#[cfg(feature = "web")]
+pub async fn load_list_from_text(list_id: &str, text: String) -> Result<LoadedList, DataError> {
+    resolve_list_file(list_id.to_string(), text, Vec::new()).await
 }
 
-pub async fn load_list(list_id: &str) -> Result<LoadedList, DataError> {
-    let url = format!("assets/lists/{}.json", list_id);
-    let response = Request::get(&url)
-        .send()
-        .await
-        .map_err(DataError::network)?;
+#[cfg(feature = "web")]
+fn resolve_list_file(
+    list_id: String,
+    text: String,
+    mut parents: Vec<String>,
+) -> Pin<Box<dyn Future<Output = Result<LoadedList, DataError>>>> {
+    Box::pin(async move {
+        let composite = match parse_list_file(&list_id, &text)? {
+            ListFile::Items(list) => return Ok(list),
+            ListFile::Composite(composite) => composite,
+        };
+        parents.push(list_id.clone());
+
+        let mut lists = HashMap::new();
+        for source in composite.sources() {
+            if parents.contains(&source) {
+                return Err(DataError::Parse(format!(
+                    "List '{}' includes itself through '{}'",
+                    list_id, source
+                )));
+            }
+            let text = fetch_list_text(&source).await?;
+            let list = resolve_list_file(source.clone(), text, parents.clone()).await?;
+            lists.insert(source, list);
+        }
+        composite.build(&lists)
+    })
+}
+
+/// Fetches a list file: bundled lists from `assets/lists/`, shared lists from
+/// their URL. The server of a shared list has to allow cross-origin requests.
+#[cfg(feature = "web")]
+pub async fn fetch_list_text(list_id: &str) -> Result<String, DataError> {
+    let remote_url = remote_list_url(list_id);
+    let url = match remote_url {
+        Some(url) => url.to_string(),
+        None => format!("assets/lists/{}.json", list_id),
+    };
+    let response = Request::get(&url).send().await.map_err(|err| match remote_url {
+        // Browsers report blocked cross-origin requests as plain fetch failures.
+        Some(_) => DataError::Network(format!(
+            "could not reach {} ({}). The server may be offline or may not allow cross-origin requests.",
+            url, err
+        )),
+        None => DataError::network(err),
+    })?;
 
     if response.status() == 404 {
-        return Err(DataError::NotFound(list_id.to_owned()));
+        return Err(DataError::NotFound(
+            remote_url.unwrap_or(list_id).to_owned(),
+        ));
     }
 
     if !response.ok() {
@@ -93,46 +642,372 @@ pub async fn load_list(list_id: &str) -> Result<LoadedList, DataError> {
         )));
     }
 
-    let text = response.text().await.map_err(DataError::network)?;
-    let raw_items: Vec<String> = serde_json::from_str(&text).map_err(DataError::parse)?;
+    response.text().await.map_err(DataError::network)
+}
+
+/// Parses `assets/index.json`. Two shapes are accepted:
+///
+/// - the legacy array of list ids, `["fruits", "boy names us"]`;
+/// - an object with a `lists` array and an optional `categories` array that
+///   fixes the order of the groups.
+///
+/// Entries of either array may be plain ids or objects with `id` and optional
+/// `title`, `description`, `category`, `count`, `thumbnail` and `updated`
+/// (`YYYY-MM-DD`). Lists come back grouped by category: categories named in
+/// `categories` first, then the rest in order of appearance, then lists
+/// without one.
+pub fn parse_list_index(text: &str) -> Result<Vec<ListInfo>, DataError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let (entries, categories) = match serde_json::from_str(text).map_err(DataError::parse)? {
+        RawIndex::Lists(entries) => (entries, Vec::new()),
+        RawIndex::Index { lists, categories } => (lists, categories),
+    };
+
+    let mut infos = Vec::with_capacity(entries.len());
+    let mut seen = HashSet::new();
+    for entry in entries {
+        let info = entry.into_info()?;
+        if !seen.insert(info.id.clone()) {
+            return Err(DataError::Parse(format!(
+                "List '{}' appears twice in the index",
+                info.id
+            )));
+        }
+        infos.push(info);
+    }
 
-    if raw_items.is_empty() {
+    let mut order: Vec<String> = categories
+        .into_iter()
+        .map(|name| name.trim().to_string())
+        .collect();
+    for info in &infos {
+        if let Some(category) = &info.category {
+            if !order.contains(category) {
+                order.push(category.clone());
+            }
+        }
+    }
+    // Stable, so lists keep their index order within a group.
+    infos.sort_by_key(|info| match &info.category {
+        Some(category) => order.iter().position(|name| name == category),
+        None => Some(order.len()),
+    });
+    Ok(infos)
+}
+
+/// Parses a list file that has its own items. Composite lists need their
+/// sources; see [`parse_list_file`].
+pub fn parse_list(list_id: &str, text: &str) -> Result<LoadedList, DataError> {
+    match parse_list_file(list_id, text)? {
+        ListFile::Items(list) => Ok(list),
+        ListFile::Composite(_) => Err(DataError::Parse(format!(
+            "List '{}' is composed of other lists",
+            list_id
+        ))),
+    }
+}
+
+/// Parses a list file. Two shapes are accepted:
+///
+/// - the legacy array of labels, `["Apple", "Banana"]`;
+/// - an object with optional `title`, `description`, `author`, `version` and
+///   `locale`, and either an `items` array or a `compose` expression.
+///
+/// Entries of either array may be plain labels or objects with `label` and
+/// optional `id`, `subtitle`, `tags` and `image`. Explicit ids are kept as
+/// given and must be unique; the rest are derived from their labels.
+///
+/// `compose` is a list id (or URL), or an object with one of `union`,
+/// `intersection` or `difference` holding further expressions, e.g.
+/// `{ "union": ["boy names us", { "difference": ["a", "b"] }] }`.
+pub fn parse_list_file(list_id: &str, text: &str) -> Result<ListFile, DataError> {
+    let value: Value = serde_json::from_str(text).map_err(DataError::parse)?;
+    let mut raw = match value {
+        Value::Array(_) => RawList {
+            title: None,
+            description: None,
+            author: None,
+            version: None,
+            locale: None,
+            items: serde_json::from_value(value).map_err(DataError::parse)?,
+            compose: None,
+        },
+        Value::Object(_) => serde_json::from_value(value).map_err(DataError::parse)?,
+        _ => {
+            return Err(DataError::Parse(format!(
+                "List '{}' must be an array of items or an object with `items`",
+                list_id
+            )))
+        }
+    };
+
+    let Some(compose) = raw.compose.take() else {
+        return build_list(list_id, raw).map(ListFile::Items);
+    };
+    if !raw.items.is_empty() {
+        return Err(DataError::Parse(format!(
+            "List '{}' has both `items` and `compose`",
+            list_id
+        )));
+    }
+    Ok(ListFile::Composite(CompositeList {
+        expr: compose.into_expr(list_id)?,
+        info: list_info(list_id, raw),
+    }))
+}
+
+/// Builds a list from items made outside a list file. Items with an empty id
+/// get one derived from their label.
+pub(crate) fn list_from_items(
+    list_id: &str,
+    title: Option<String>,
+    items: Vec<ListItem>,
+) -> Result<LoadedList, DataError> {
+    let raw = RawList {
+        title,
+        description: None,
+        author: None,
+        version: None,
+        locale: None,
+        compose: None,
+        items: items
+            .into_iter()
+            .map(|item| RawItem::Item {
+                id: Some(item.id).filter(|id| !id.trim().is_empty()),
+                label: item.label,
+                subtitle: item.subtitle,
+                tags: item.tags,
+                image: item.image,
+            })
+            .collect(),
+    };
+    build_list(list_id, raw)
+}
+
+/// Shared validation for every list source: at least one item, no empty
+/// labels, unique explicit ids and derived ids for the rest.
+fn build_list(list_id: &str, mut raw: RawList) -> Result<LoadedList, DataError> {
+    if raw.items.is_empty() {
         return Err(DataError::Parse(format!(
             "List '{}' does not contain any items",
             list_id
         )));
     }
 
-    let mut seen = HashSet::new();
-    let mut items = Vec::with_capacity(raw_items.len());
+    let mut items = Vec::with_capacity(raw.items.len());
+    let mut explicit_ids = HashSet::new();
+    for (index, raw_item) in std::mem::take(&mut raw.items).into_iter().enumerate() {
+        let item = match raw_item {
+            RawItem::Label(label) => ListItem {
+                label,
+                ..ListItem::default()
+            },
+            RawItem::Item {
+                id,
+                label,
+                subtitle,
+                tags,
+                image,
+            } => ListItem {
+                id: id.map(|id| id.trim().to_string()).unwrap_or_default(),
+                label,
+                subtitle: non_empty(subtitle),
+                tags: tags
+                    .into_iter()
+                    .map(|tag| tag.trim().to_string())
+                    .filter(|tag| !tag.is_empty())
+                    .collect(),
+                // A shared list's relative images live next to the list file.
+                image: non_empty(image).map(|image| match remote_list_url(list_id) {
+                    Some(list_url) => join_url(list_url, &image),
+                    None => image,
+                }),
+            },
+        };
 
-    for (index, label) in raw_items.into_iter().enumerate() {
-        let trimmed = label.trim().to_string();
-        if trimmed.is_empty() {
+        let label = item.label.trim().to_string();
+        if label.is_empty() {
             return Err(DataError::Parse(format!(
                 "Item {} in list '{}' is empty",
                 index, list_id
             )));
         }
-
-        let mut candidate = slugify(&trimmed);
-        if candidate.is_empty() {
-            candidate = format!("item-{}", index);
+        if !item.id.is_empty() && !explicit_ids.insert(item.id.clone()) {
+            return Err(DataError::Parse(format!(
+                "Item id '{}' appears more than once in list '{}'",
+                item.id, list_id
+            )));
         }
+        items.push(ListItem { label, ..item });
+    }
 
-        let id = ensure_unique_id(&mut seen, candidate);
-        items.push(ListItem { id, label: trimmed });
+    let derived: Vec<usize> = (0..items.len())
+        .filter(|&index| items[index].id.is_empty())
+        .collect();
+    let labels: Vec<String> = derived
+        .iter()
+        .map(|&index| items[index].label.clone())
+        .collect();
+    for (index, id) in derived
+        .into_iter()
+        .zip(assign_item_ids(&labels, explicit_ids))
+    {
+        items[index].id = id;
     }
 
     Ok(LoadedList {
-        info: ListInfo {
-            id: list_id.to_owned(),
-            label: display_name(list_id),
-        },
+        info: list_info(list_id, raw),
         items,
+        sources: Vec::new(),
     })
 }
 
+fn list_info(list_id: &str, raw: RawList) -> ListInfo {
+    ListInfo {
+        id: list_id.to_owned(),
+        label: non_empty(raw.title).unwrap_or_else(|| default_title(list_id)),
+        description: non_empty(raw.description),
+        author: non_empty(raw.author),
+        version: raw.version.map(|version| match version {
+            RawVersion::Text(text) => text.trim().to_string(),
+            RawVersion::Number(number) => number.to_string(),
+        }),
+        locale: non_empty(raw.locale),
+        ..ListInfo::default()
+    }
+}
+
+/// Resolves an item's `image`. Absolute URLs, data URIs and root-relative
+/// paths are used as they are; anything else is a bundled file under `assets/`.
+/// Shared lists never reach the last case: their relative images are resolved
+/// against the list's URL when it is parsed.
+pub fn resolve_image_url(path: &str) -> String {
+    let path = path.trim();
+    if has_url_scheme(path) || path.starts_with('/') {
+        path.to_string()
+    } else {
+        format!("assets/{}", path.trim_start_matches("./"))
+    }
+}
+
+fn has_url_scheme(path: &str) -> bool {
+    path.split_once(':').is_some_and(|(scheme, _)| {
+        !scheme.is_empty() && scheme.chars().all(|ch| ch.is_ascii_alphabetic())
+    })
+}
+
+/// Resolves `path` against the absolute URL `base` the way a browser resolves
+/// a link: `//host/...`, `/...`, `./` and `../` are all honoured, and paths
+/// that already have a scheme are returned as they are.
+fn join_url(base: &str, path: &str) -> String {
+    let path = path.trim();
+    if has_url_scheme(path) {
+        return path.to_string();
+    }
+    let base = base.split(['?', '#']).next().unwrap_or(base);
+    let (scheme, rest) = base.split_once("://").unwrap_or(("https", base));
+    if let Some(network_path) = path.strip_prefix("//") {
+        return format!("{}://{}", scheme, network_path);
+    }
+    let (host, base_path) = rest.split_once('/').unwrap_or((rest, ""));
+    let (path, suffix) = path.split_at(path.find(['?', '#']).unwrap_or(path.len()));
+
+    let mut segments: Vec<&str> = if path.starts_with('/') {
+        Vec::new()
+    } else {
+        let mut directory: Vec<&str> = base_path.split('/').collect();
+        // Drop the list's own file name.
+        directory.pop();
+        directory
+    };
+    for segment in path.trim_start_matches('/').split('/') {
+        match segment {
+            "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("{}://{}/{}{}", scheme, host, segments.join("/"), suffix)
+}
+
+fn non_empty(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// Slugs every label and resolves collisions without depending on list order:
+/// within a group of labels that share a slug, the smallest label keeps the
+/// plain slug and the others get a suffix hashed from their own label. Only
+/// exact duplicate labels fall back to a counter. Ids in `taken` are never
+/// handed out.
+fn assign_item_ids(labels: &[String], taken: HashSet<String>) -> Vec<String> {
+    let bases: Vec<String> = labels
+        .iter()
+        .enumerate()
+        .map(|(index, label)| {
+            let slug = slugify(label);
+            if slug.is_empty() {
+                format!("item-{}", index)
+            } else {
+                slug
+            }
+        })
+        .collect();
+
+    let mut groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (index, base) in bases.iter().enumerate() {
+        groups.entry(base.as_str()).or_default().push(index);
+    }
+
+    let mut candidates = bases.clone();
+    for members in groups.values().filter(|members| members.len() > 1) {
+        let owner = members
+            .iter()
+            .copied()
+            .min_by(|&a, &b| labels[a].cmp(&labels[b]).then(a.cmp(&b)))
+            .unwrap_or(members[0]);
+        for &index in members {
+            if labels[index] != labels[owner] {
+                candidates[index] = format!("{}-{:06x}", bases[index], label_hash(&labels[index]));
+            }
+        }
+    }
+
+    let mut seen = taken;
+    candidates
+        .into_iter()
+        .map(|candidate| ensure_unique_id(&mut seen, candidate))
+        .collect()
+}
+
+/// Ids the list would have had before labels were transliterated, in item
+/// order. Used to carry saved progress over to the current ids.
+pub fn legacy_item_ids(list: &LoadedList) -> Vec<String> {
+    let mut seen = HashSet::new();
+    list.items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            let mut candidate = legacy_slugify(&item.label);
+            if candidate.is_empty() {
+                candidate = format!("item-{}", index);
+            }
+            ensure_unique_id(&mut seen, candidate)
+        })
+        .collect()
+}
+
+/// 24-bit FNV-1a, stable across builds and platforms.
+fn label_hash(label: &str) -> u32 {
+    let hash = label.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+        (hash ^ u32::from(byte)).wrapping_mul(0x0100_0193)
+    });
+    (hash >> 8) ^ (hash & 0xff)
+}
+
 fn ensure_unique_id(seen: &mut HashSet<String>, base: String) -> String {
     if seen.insert(base.clone()) {
         return base;
@@ -148,8 +1023,25 @@ fn ensure_unique_id(seen: &mut HashSet<String>, base: String) -> String {
     }
 }
 
+/// Title for a list file without one: the id, or the file name of a shared
+/// list's URL.
+fn default_title(list_id: &str) -> String {
+    match remote_list_url(list_id) {
+        Some(url) => {
+            let path = url.split(['?', '#']).next().unwrap_or(url);
+            let file = path
+                .trim_end_matches('/')
+                .rsplit('/')
+                .next()
+                .unwrap_or(path);
+            display_name(file.split('.').next().unwrap_or(file))
+        }
+        None => display_name(list_id),
+    }
+}
+
 fn display_name(id: &str) -> String {
-    id.split(|c: char| c == '_' || c == '-' || c == ' ')
+    id.split(['_', '-', ' '])
         .filter(|segment| !segment.is_empty())
         .map(|segment| {
             let mut chars = segment.chars();
@@ -164,16 +1056,62 @@ fn display_name(id: &str) -> String {
         .join(" ")
 }
 
+/// Lowercase, hyphen-separated id for a label. Latin letters are
+/// transliterated (`č` -> `c`, `đ` -> `dj`, `ß` -> `ss`); other scripts such as
+/// CJK are kept as they are.
 fn slugify(input: &str) -> String {
     let mut slug = String::new();
 
+    for ch in input.nfc() {
+        if let Some(replacement) = transliterate(ch) {
+            slug.push_str(replacement);
+        } else if ch.is_ascii_alphanumeric() {
+            slug.push(ch.to_ascii_lowercase());
+        } else if (ch.is_whitespace() || matches!(ch, '-' | '_')) && !slug.ends_with('-') {
+            slug.push('-');
+        } else if !ch.is_ascii() {
+            let decomposed: String = ch.nfkd().filter(|c| c.is_ascii_alphanumeric()).collect();
+            if !decomposed.is_empty() && ch.nfkd().all(|c| c.is_ascii() || is_combining_mark(c)) {
+                slug.push_str(&decomposed.to_ascii_lowercase());
+            } else if ch.is_alphanumeric() {
+                slug.extend(ch.to_lowercase());
+            }
+        }
+    }
+
+    slug.trim_matches('-').to_string()
+}
+
+/// Letters that have no canonical decomposition into ASCII.
+fn transliterate(ch: char) -> Option<&'static str> {
+    let replacement = match ch {
+        'đ' | 'Đ' => "dj",
+        'ß' | 'ẞ' => "ss",
+        'æ' | 'Æ' => "ae",
+        'œ' | 'Œ' => "oe",
+        'ø' | 'Ø' => "o",
+        'ł' | 'Ł' => "l",
+        'þ' | 'Þ' => "th",
+        'ð' | 'Ð' => "d",
+        'ı' => "i",
+        _ => return None,
+    };
+    Some(replacement)
+}
+
+fn is_combining_mark(ch: char) -> bool {
+    matches!(ch, '\u{0300}'..='\u{036f}' | '\u{1ab0}'..='\u{1aff}' | '\u{1dc0}'..='\u{1dff}')
+}
+
+/// The ASCII-only slug used before transliteration; kept for migrations.
+fn legacy_slugify(input: &str) -> String {
+    let mut slug = String::new();
+
     for ch in input.chars() {
         if ch.is_ascii_alphanumeric() {
             slug.push(ch.to_ascii_lowercase());
-        } else if ch.is_whitespace() || matches!(ch, '-' | '_') {
-            if !slug.ends_with('-') {
-                slug.push('-');
-            }
+        } else if (ch.is_whitespace() || matches!(ch, '-' | '_')) && !slug.ends_with('-') {
+            slug.push('-');
         }
     }
 
@@ -190,6 +1128,339 @@ mod tests {
         assert_eq!(slugify("  Mango!!!  "), "mango");
     }
 
+    #[test]
+    fn slugify_transliterates_latin_and_keeps_cjk() {
+        assert_eq!(slugify("Ćiro Čačić"), "ciro-cacic");
+        assert_eq!(slugify("Đuro"), "djuro");
+        assert_eq!(slugify("Straße"), "strasse");
+        assert_eq!(slugify("C\u{30c}iro"), "ciro");
+        assert_eq!(slugify("東京 Tower"), "東京-tower");
+        assert_eq!(slugify("Ｆｕｌｌ"), "full");
+    }
+
+    #[test]
+    fn colliding_ids_do_not_depend_on_order() {
+        let forward = assign_item_ids(&["Ciro".to_string(), "Ćiro".to_string()], HashSet::new());
+        let backward = assign_item_ids(&["Ćiro".to_string(), "Ciro".to_string()], HashSet::new());
+
+        assert_eq!(forward[0], "ciro");
+        assert!(forward[1].starts_with("ciro-"));
+        assert_eq!(forward[0], backward[1]);
+        assert_eq!(forward[1], backward[0]);
+    }
+
+    #[test]
+    fn legacy_ids_match_the_ascii_scheme() {
+        let list = parse_list("names", r#"["Ćiro", "Ivo", "Ivo"]"#).unwrap();
+        assert_eq!(legacy_item_ids(&list), ["iro", "ivo", "ivo-2"]);
+    }
+
+    #[test]
+    fn parse_list_derives_unique_ids() {
+        let list = parse_list("fruit", r#"["Green Apple", "green apple", "Kiwi"]"#).unwrap();
+        let ids: Vec<&str> = list.items.iter().map(|item| item.id.as_str()).collect();
+
+        // Different labels that share a slug get a suffix hashed from the label.
+        assert_eq!(ids, ["green-apple", "green-apple-6288cc", "kiwi"]);
+        assert_eq!(list.info.label, "Fruit");
+        assert!(matches!(
+            parse_list("fruit", "[]"),
+            Err(DataError::Parse(_))
+        ));
+        assert!(matches!(
+            parse_list("fruit", r#"["Kiwi", "  "]"#),
+            Err(DataError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn parse_list_transliterates_ids() {
+        let list = parse_list("names", r#"["Čedo", "Đurđa", "Jürgen Weiß", "東京"]"#).unwrap();
+        let ids: Vec<&str> = list.items.iter().map(|item| item.id.as_str()).collect();
+
+        assert_eq!(ids, ["cedo", "djurdja", "jurgen-weiss", "東京"]);
+    }
+
+    #[test]
+    fn parse_list_reads_the_object_schema() {
+        let text = r#"{
+            "title": "Croatian names",
+            "description": "Popular names",
+            "author": "Marin",
+            "version": 2,
+            "locale": "hr",
+            "items": [
+                { "id": "ciro", "label": "Ćiro", "subtitle": "short for Ćiril", "tags": ["classic", " "] },
+                { "label": "Ivo", "image": "images/ivo.jpg" },
+                "Ciro"
+            ]
+        }"#;
+        let list = parse_list("boy-names-cro", text).unwrap();
+
+        assert_eq!(list.info.label, "Croatian names");
+        assert_eq!(list.info.version.as_deref(), Some("2"));
+        assert_eq!(list.info.locale.as_deref(), Some("hr"));
+        assert_eq!(list.items[0].id, "ciro");
+        assert_eq!(list.items[0].tags, ["classic"]);
+        assert_eq!(list.items[1].id, "ivo");
+        assert_eq!(list.items[1].image.as_deref(), Some("images/ivo.jpg"));
+        // The derived id must not steal an explicit one.
+        assert_eq!(list.items[2].id, "ciro-2");
+    }
+
+    #[test]
+    fn parse_list_rejects_duplicate_explicit_ids() {
+        let text = r#"{ "items": [{ "id": "a", "label": "A" }, { "id": "a", "label": "B" }] }"#;
+        assert!(matches!(parse_list("x", text), Err(DataError::Parse(_))));
+        assert!(matches!(parse_list("x", "42"), Err(DataError::Parse(_))));
+    }
+
+    #[test]
+    fn image_paths_resolve_to_assets() {
+        assert_eq!(resolve_image_url("images/ivo.jpg"), "assets/images/ivo.jpg");
+        assert_eq!(resolve_image_url("./logo.png"), "assets/logo.png");
+        assert_eq!(resolve_image_url("/static/a.png"), "/static/a.png");
+        assert_eq!(
+            resolve_image_url("https://example.com/a.png"),
+            "https://example.com/a.png"
+        );
+        assert!(resolve_image_url("data:image/png;base64,AAAA").starts_with("data:"));
+    }
+
+    #[test]
+    fn shared_list_images_resolve_against_the_list_url() {
+        let base = "https://example.com/lists/films.json?v=2";
+        assert_eq!(
+            join_url(base, "posters/a.jpg"),
+            "https://example.com/lists/posters/a.jpg"
+        );
+        assert_eq!(join_url(base, "./a.jpg"), "https://example.com/lists/a.jpg");
+        assert_eq!(
+            join_url(base, "../img/a.jpg?s=1"),
+            "https://example.com/img/a.jpg?s=1"
+        );
+        assert_eq!(join_url(base, "/a.jpg"), "https://example.com/a.jpg");
+        assert_eq!(
+            join_url(base, "//cdn.example.org/a.jpg"),
+            "https://cdn.example.org/a.jpg"
+        );
+        assert_eq!(
+            join_url(base, "data:image/png;base64,AAAA"),
+            "data:image/png;base64,AAAA"
+        );
+
+        let text = r#"{ "items": [{ "label": "Heat", "image": "posters/heat.jpg" }, "Ran"] }"#;
+        let list = parse_list("url:https://example.com/lists/films.json", text).unwrap();
+        assert_eq!(
+            list.items[0].image.as_deref(),
+            Some("https://example.com/lists/posters/heat.jpg")
+        );
+        let bundled = parse_list("films", text).unwrap();
+        assert_eq!(bundled.items[0].image.as_deref(), Some("posters/heat.jpg"));
+    }
+
+    #[test]
+    fn custom_lists_keep_ids_across_renames() {
+        let mut list = CustomList::create("My Snacks", ["custom:my-snacks"]);
+        assert_eq!(list.id, "custom:my-snacks-2");
+
+        list.items = vec![
+            CustomItem {
+                id: String::new(),
+                label: " Chips ".to_string(),
+                ..CustomItem::default()
+            },
+            CustomItem {
+                id: String::new(),
+                label: "Nuts".to_string(),
+                ..CustomItem::default()
+            },
+        ];
+        list.finalize().unwrap();
+        assert_eq!(list.items[0].id, "chips");
+        assert_eq!(list.items[0].label, "Chips");
+
+        list.items[0].label = "Crisps".to_string();
+        list.items.swap(0, 1);
+        let loaded = list.finalize().unwrap();
+        assert_eq!(loaded.items[1].id, "chips");
+        assert_eq!(loaded.items[1].label, "Crisps");
+
+        list.items.clear();
+        assert!(matches!(list.finalize(), Err(DataError::Parse(_))));
+    }
+
+    #[test]
+    fn remote_lists_are_keyed_by_url() {
+        let id = remote_list_id(" https://example.com/lists/best_films.json?v=2 ").unwrap();
+        assert_eq!(id, "url:https://example.com/lists/best_films.json?v=2");
+        assert_eq!(
+            remote_list_url(&id),
+            Some("https://example.com/lists/best_films.json?v=2")
+        );
+        assert_eq!(remote_list_id("ftp://example.com/a.json"), None);
+        assert_eq!(remote_list_id("https:///a.json"), None);
+        assert_eq!(remote_list_id("https://example.com/a b.json"), None);
+
+        let list = parse_list(&id, r#"["A", "B"]"#).unwrap();
+        assert_eq!(list.info.label, "Best Films");
+    }
+
+    #[test]
+    fn composite_lists_combine_sources_by_id() {
+        let text = r#"{
+            "title": "Mixed",
+            "compose": { "union": [
+                "a",
+                { "difference": ["b", "c"] },
+                { "intersection": ["https://example.com/d.json", "a"] }
+            ] }
+        }"#;
+        let ListFile::Composite(composite) = parse_list_file("mixed", text).unwrap() else {
+            panic!("expected a composite list");
+        };
+        assert_eq!(
+            composite.sources(),
+            ["a", "b", "c", "url:https://example.com/d.json"]
+        );
+
+        let mut lists = HashMap::new();
+        lists.insert(
+            "a".to_string(),
+            parse_list("a", r#"["Ivo", "Liam"]"#).unwrap(),
+        );
+        lists.insert(
+            "b".to_string(),
+            parse_list(
+                "b",
+                r#"[{ "label": "Liam", "tags": ["uk"] }, "Noah", "Jack"]"#,
+            )
+            .unwrap(),
+        );
+        lists.insert("c".to_string(), parse_list("c", r#"["Jack"]"#).unwrap());
+        let d = "url:https://example.com/d.json".to_string();
+        lists.insert(d.clone(), parse_list(&d, r#"["Ivo", "Oscar"]"#).unwrap());
+
+        let list = composite.build(&lists).unwrap();
+        let ids: Vec<&str> = list.items.iter().map(|item| item.id.as_str()).collect();
+        assert_eq!(ids, ["ivo", "liam", "noah"]);
+        assert_eq!(list.items[1].tags, ["uk"]);
+        assert_eq!(list.info.label, "Mixed");
+        assert_eq!(list.sources, composite.sources());
+
+        lists.remove("c");
+        assert!(matches!(
+            composite.build(&lists),
+            Err(DataError::NotFound(_))
+        ));
+        assert!(matches!(
+            parse_list("mixed", text),
+            Err(DataError::Parse(_))
+        ));
+        assert!(parse_list_file("x", r#"{ "items": ["A"], "compose": "a" }"#).is_err());
+        assert!(parse_list_file("x", r#"{ "compose": { "union": [] } }"#).is_err());
+    }
+
+    /// A shipped list from `assets/lists/`, composed from its sources if it
+    /// has any.
+    fn bundled_list(list_id: &str) -> LoadedList {
+        let path = format!(
+            "{}/assets/lists/{}.json",
+            env!("CARGO_MANIFEST_DIR"),
+            list_id
+        );
+        let text = std::fs::read_to_string(path).unwrap();
+        match parse_list_file(list_id, &text).unwrap() {
+            ListFile::Items(list) => list,
+            ListFile::Composite(composite) => {
+                let lists = composite
+                    .sources()
+                    .into_iter()
+                    .map(|source| {
+                        let list = bundled_list(&source);
+                        (source, list)
+                    })
+                    .collect();
+                composite.build(&lists).unwrap()
+            }
+        }
+    }
+
+    #[test]
+    fn composite_lists_merge_repeated_labels() {
+        let mut lists = HashMap::new();
+        lists.insert(
+            "a".to_string(),
+            parse_list("a", r#"["Nikolas", "Ivo", "Nikolas"]"#).unwrap(),
+        );
+        lists.insert("b".to_string(), parse_list("b", r#"["NIKOLAS"]"#).unwrap());
+        let composite = CompositeList {
+            info: ListInfo::default(),
+            expr: ListExpr::Union(vec![
+                ListExpr::List("a".to_string()),
+                ListExpr::List("b".to_string()),
+            ]),
+        };
+        let labels: Vec<String> = composite
+            .build(&lists)
+            .unwrap()
+            .items
+            .into_iter()
+            .map(|item| item.label)
+            .collect();
+        assert_eq!(labels, ["Nikolas", "Ivo"]);
+
+        let shipped = bundled_list("all boy names");
+        let mut seen = HashSet::new();
+        for item in &shipped.items {
+            assert!(
+                seen.insert(label_key(&item.label)),
+                "{} appears twice",
+                item.label
+            );
+        }
+    }
+
+    #[test]
+    fn list_index_reads_both_shapes() {
+        let legacy = parse_list_index("\u{feff}[\"fruits\", \"boy_names\"]").unwrap();
+        assert_eq!(legacy[1].label, "Boy Names");
+        assert_eq!(legacy[1].item_count, None);
+
+        let text = r#"{
+            "categories": ["Names"],
+            "lists": [
+                { "id": "fruits", "category": "Food", "count": 8 },
+                "loose",
+                { "id": "boys", "title": "Boys", "category": "Names",
+                  "description": "Popular names", "thumbnail": "thumbs/boys.png",
+                  "updated": "2024-05-01" }
+            ]
+        }"#;
+        let lists = parse_list_index(text).unwrap();
+        let ids: Vec<&str> = lists.iter().map(|info| info.id.as_str()).collect();
+        assert_eq!(ids, ["boys", "fruits", "loose"]);
+        assert_eq!(lists[0].updated.as_deref(), Some("2024-05-01"));
+        assert_eq!(lists[1].item_count, Some(8));
+        assert!(lists[0].matches_search("POPULAR names"));
+        assert!(lists[1].matches_search("food"));
+        assert!(!lists[2].matches_search("food"));
+
+        assert!(parse_list_index(r#"[{ "id": "a", "updated": "May 2024" }]"#).is_err());
+        assert!(parse_list_index(r#"["a", { "id": "a" }]"#).is_err());
+        assert!(parse_list_index(include_str!("../assets/index.json")).is_ok());
+    }
+
+    #[test]
+    fn shipped_index_counts_match_the_lists() {
+        for info in parse_list_index(include_str!("../assets/index.json")).unwrap() {
+            let list = bundled_list(&info.id);
+            if let Some(count) = info.item_count {
+                assert_eq!(count, list.items.len(), "count of '{}'", info.id);
+            }
+        }
+    }
+
     #[test]
     fn display_name_basic() {
         assert_eq!(display_name("citrus-fruits"), "Citrus Fruits");
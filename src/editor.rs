@@ -0,0 +1,402 @@
+use crate::data::{remote_list_id, CustomItem, CustomList};
+use crate::import::{column_names, import_list, title_from_file_name, ImportFormat, ImportOptions};
+use web_sys::{HtmlInputElement, HtmlSelectElement, HtmlTextAreaElement};
+use yew::prelude::*;
+
+#[derive(Properties, PartialEq)]
+pub struct ListEditorProps {
+    pub list: CustomList,
+    pub is_new: bool,
+    pub on_save: Callback<CustomList>,
+    pub on_cancel: Callback<()>,
+    pub on_delete: Callback<String>,
+}
+
+/// Full-screen editor for a custom list. Nothing is stored until the draft
+/// passes the same validation as a bundled list file.
+#[function_component(ListEditor)]
+pub fn list_editor(props: &ListEditorProps) -> Html {
+    let draft = use_state(|| props.list.clone());
+    let pasted = use_state(String::new);
+    let error = use_state(|| None::<String>);
+    let confirm_delete = use_state(|| false);
+
+    let update = {
+        let draft = draft.clone();
+        move |change: &dyn Fn(&mut CustomList)| {
+            let mut next = (*draft).clone();
+            change(&mut next);
+            draft.set(next);
+        }
+    };
+
+    let on_title_input = {
+        let update = update.clone();
+        Callback::from(move |event: InputEvent| {
+            let title = event.target_unchecked_into::<HtmlInputElement>().value();
+            update(&|list: &mut CustomList| list.title = title.clone());
+        })
+    };
+
+    let on_paste_input = {
+        let pasted = pasted.clone();
+        Callback::from(move |event: InputEvent| {
+            pasted.set(event.target_unchecked_into::<HtmlTextAreaElement>().value());
+        })
+    };
+
+    let on_add_items = {
+        let update = update.clone();
+        let pasted = pasted.clone();
+        Callback::from(move |_: MouseEvent| {
+            let labels: Vec<String> = pasted
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty())
+                .map(str::to_string)
+                .collect();
+            if labels.is_empty() {
+                return;
+            }
+            update(&|list: &mut CustomList| {
+                list.items.extend(labels.iter().map(|label| CustomItem {
+                    label: label.clone(),
+                    ..CustomItem::default()
+                }));
+            });
+            pasted.set(String::new());
+        })
+    };
+
+    let on_save = {
+        let draft = draft.clone();
+        let error = error.clone();
+        let on_save = props.on_save.clone();
+        Callback::from(move |_: MouseEvent| {
+            let mut list = (*draft).clone();
+            match list.finalize() {
+                Ok(_) => {
+                    error.set(None);
+                    on_save.emit(list);
+                }
+                Err(err) => error.set(Some(err.to_string())),
+            }
+        })
+    };
+
+    let on_cancel = {
+        let on_cancel = props.on_cancel.clone();
+        Callback::from(move |_: MouseEvent| on_cancel.emit(()))
+    };
+
+    let delete_controls = if props.is_new {
+        html! {}
+    } else if *confirm_delete {
+        let on_delete = props.on_delete.clone();
+        let id = draft.id.clone();
+        let confirm_delete = confirm_delete.clone();
+        html! {
+            <div class="editor-confirm">
+                <p>{ "Delete this list and its rankings?" }</p>
+                <div class="editor-actions">
+                    <button class="editor-delete" onclick={Callback::from(move |_| on_delete.emit(id.clone()))}>
+                        { "Delete" }
+                    </button>
+                    <button class="editor-cancel" onclick={Callback::from(move |_| confirm_delete.set(false))}>
+                        { "Keep" }
+                    </button>
+                </div>
+            </div>
+        }
+    } else {
+        let confirm_delete = confirm_delete.clone();
+        html! {
+            <button class="editor-delete" onclick={Callback::from(move |_| confirm_delete.set(true))}>
+                { "Delete list" }
+            </button>
+        }
+    };
+
+    let item_count = draft.items.len();
+    let rows = draft.items.iter().enumerate().map(|(index, item)| {
+        let on_rename = {
+            let update = update.clone();
+            Callback::from(move |event: InputEvent| {
+                let label = event.target_unchecked_into::<HtmlInputElement>().value();
+                update(&|list: &mut CustomList| {
+                    if let Some(item) = list.items.get_mut(index) {
+                        item.label = label.clone();
+                    }
+                });
+            })
+        };
+        let on_move = |offset: isize| {
+            let update = update.clone();
+            Callback::from(move |_: MouseEvent| {
+                update(&|list: &mut CustomList| {
+                    let target = index as isize + offset;
+                    if target >= 0 && (target as usize) < list.items.len() {
+                        list.items.swap(index, target as usize);
+                    }
+                });
+            })
+        };
+        let on_remove = {
+            let update = update.clone();
+            Callback::from(move |_: MouseEvent| {
+                update(&|list: &mut CustomList| {
+                    if index < list.items.len() {
+                        list.items.remove(index);
+                    }
+                });
+            })
+        };
+
+        html! {
+            <li class="editor-item" key={format!("{index}-{}", item.id)}>
+                <input class="editor-item-label" value={item.label.clone()} oninput={on_rename} />
+                <button class="editor-icon" title="Move up" disabled={index == 0} onclick={on_move(-1)}>{ "↑" }</button>
+                <button class="editor-icon" title="Move down" disabled={index + 1 == item_count} onclick={on_move(1)}>{ "↓" }</button>
+                <button class="editor-icon remove" title="Remove" onclick={on_remove}>{ "×" }</button>
+            </li>
+        }
+    });
+
+    html! {
+        <div class="editor-overlay" data-swipe-ignore="true">
+            <div class="list-editor">
+                <h2>{ if props.is_new { "New list" } else { "Edit list" } }</h2>
+                <input class="editor-title"
+                    placeholder="List title"
+                    value={draft.title.clone()}
+                    oninput={on_title_input} />
+                <textarea class="editor-paste"
+                    placeholder="Paste or type items, one per line"
+                    value={(*pasted).clone()}
+                    oninput={on_paste_input} />
+                <button class="editor-add" onclick={on_add_items}>{ "Add items" }</button>
+                <p class="editor-count">{ format!("{item_count} items") }</p>
+                <ol class="editor-items">
+                    { for rows }
+                </ol>
+                {
+                    match (*error).as_ref() {
+                        Some(message) => html! { <p class="editor-error">{ message }</p> },
+                        None => html! {},
+                    }
+                }
+                <div class="editor-actions">
+                    <button class="editor-cancel" onclick={on_cancel}>{ "Cancel" }</button>
+                    <button class="editor-save" onclick={on_save}>{ "Save" }</button>
+                </div>
+                { delete_controls }
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct ImportDialogProps {
+    pub file_name: String,
+    pub text: String,
+    pub on_import: Callback<CustomList>,
+    pub on_cancel: Callback<()>,
+}
+
+/// Rows shown before the rest of the errors are summarised.
+const MAX_SHOWN_ERRORS: usize = 12;
+
+/// Column choices for an imported file. The file is re-read on every change,
+/// so problems are listed with their line numbers before anything is saved.
+#[function_component(ImportDialog)]
+pub fn import_dialog(props: &ImportDialogProps) -> Html {
+    let options = {
+        let file_name = props.file_name.clone();
+        let text = props.text.clone();
+        use_state(move || ImportOptions::detect(ImportFormat::from_file_name(&file_name), &text))
+    };
+
+    let title = title_from_file_name(&props.file_name);
+    let result = import_list("", &title, &props.text, &options);
+    let columns = column_names(&props.text, &options);
+
+    let format_buttons = ImportFormat::ALL.iter().map(|&format| {
+        let options_handle = options.clone();
+        let text = props.text.clone();
+        let class = classes!(
+            "editor-choice",
+            if options.format == format { Some("active") } else { None }
+        );
+        html! {
+            <button {class} onclick={Callback::from(move |_| options_handle.set(ImportOptions::detect(format, &text)))}>
+                { format.label() }
+            </button>
+        }
+    });
+
+    let column_select = |label: &str,
+                         selected: Option<usize>,
+                         optional: bool,
+                         apply: fn(&mut ImportOptions, Option<usize>)| {
+        let options_handle = options.clone();
+        let onchange = Callback::from(move |event: Event| {
+            let value = event.target_unchecked_into::<HtmlSelectElement>().value();
+            let mut next = (*options_handle).clone();
+            apply(&mut next, value.parse().ok());
+            options_handle.set(next);
+        });
+        html! {
+            <label class="editor-field">
+                <span>{ label }</span>
+                <select {onchange}>
+                    {
+                        if optional {
+                            html! { <option value="" selected={selected.is_none()}>{ "None" }</option> }
+                        } else {
+                            html! {}
+                        }
+                    }
+                    { for columns.iter().enumerate().map(|(index, name)| html! {
+                        <option value={index.to_string()} selected={selected == Some(index)}>{ name }</option>
+                    }) }
+                </select>
+            </label>
+        }
+    };
+
+    let table_fields = if options.format == ImportFormat::Text {
+        html! {}
+    } else {
+        let options_handle = options.clone();
+        let on_header = Callback::from(move |event: Event| {
+            let mut next = (*options_handle).clone();
+            next.has_header = event.target_unchecked_into::<HtmlInputElement>().checked();
+            options_handle.set(next);
+        });
+        html! {
+            <>
+                <label class="editor-field">
+                    <span>{ "First row names the columns" }</span>
+                    <input type="checkbox" checked={options.has_header} onchange={on_header} />
+                </label>
+                { column_select("Label column", Some(options.label_column), false, |options, column| {
+                    options.label_column = column.unwrap_or(0);
+                }) }
+                { column_select("Id column", options.id_column, true, |options, column| {
+                    options.id_column = column;
+                }) }
+                { column_select("Tags column", options.tags_column, true, |options, column| {
+                    options.tags_column = column;
+                }) }
+            </>
+        }
+    };
+
+    let (summary, on_continue) = match &result {
+        Ok(list) => {
+            let on_import = props.on_import.clone();
+            let draft = CustomList::from_loaded(list);
+            (
+                html! { <p class="editor-count">{ format!("{} items ready", list.items.len()) }</p> },
+                Some(Callback::from(move |_: MouseEvent| {
+                    on_import.emit(draft.clone())
+                })),
+            )
+        }
+        Err(errors) => {
+            let hidden = errors.len().saturating_sub(MAX_SHOWN_ERRORS);
+            (
+                html! {
+                    <ul class="editor-errors">
+                        { for errors.iter().take(MAX_SHOWN_ERRORS).map(|err| html! { <li>{ err.to_string() }</li> }) }
+                        {
+                            if hidden > 0 {
+                                html! { <li>{ format!("…and {} more", hidden) }</li> }
+                            } else {
+                                html! {}
+                            }
+                        }
+                    </ul>
+                },
+                None,
+            )
+        }
+    };
+
+    let on_cancel = {
+        let on_cancel = props.on_cancel.clone();
+        Callback::from(move |_: MouseEvent| on_cancel.emit(()))
+    };
+
+    html! {
+        <div class="editor-overlay" data-swipe-ignore="true">
+            <div class="list-editor">
+                <h2>{ format!("Import {}", props.file_name) }</h2>
+                <div class="editor-choices">{ for format_buttons }</div>
+                { table_fields }
+                { summary }
+                <div class="editor-actions">
+                    <button class="editor-cancel" onclick={on_cancel}>{ "Cancel" }</button>
+                    <button class="editor-save" disabled={on_continue.is_none()} onclick={on_continue}>
+                        { "Continue" }
+                    </button>
+                </div>
+            </div>
+        </div>
+    }
+}
+
+#[derive(Properties, PartialEq)]
+pub struct OpenUrlFormProps {
+    /// Receives the list id for the entered URL.
+    pub on_open: Callback<String>,
+}
+
+/// Input for opening a list file from another site.
+#[function_component(OpenUrlForm)]
+pub fn open_url_form(props: &OpenUrlFormProps) -> Html {
+    let url = use_state(String::new);
+    let error = use_state(|| None::<String>);
+
+    let on_input = {
+        let url = url.clone();
+        Callback::from(move |event: InputEvent| {
+            url.set(event.target_unchecked_into::<HtmlInputElement>().value());
+        })
+    };
+
+    let on_submit = {
+        let url = url.clone();
+        let error = error.clone();
+        let on_open = props.on_open.clone();
+        Callback::from(move |event: SubmitEvent| {
+            event.prevent_default();
+            match remote_list_id(&url) {
+                Some(list_id) => {
+                    error.set(None);
+                    url.set(String::new());
+                    on_open.emit(list_id);
+                }
+                None => error.set(Some(
+                    "Enter a full http:// or https:// address.".to_string(),
+                )),
+            }
+        })
+    };
+
+    html! {
+        <form class="url-form" onsubmit={on_submit}>
+            <input type="url"
+                placeholder="https://example.com/list.json"
+                value={(*url).clone()}
+                oninput={on_input} />
+            <button type="submit">{ "Open" }</button>
+            {
+                match (*error).as_ref() {
+                    Some(message) => html! { <p class="menu-error">{ message }</p> },
+                    None => html! {},
+                }
+            }
+        </form>
+    }
+}
@@ -0,0 +1,287 @@
+use crate::data::ListItem;
+use crate::filter::ItemFilter;
+use crate::ranking::RatingEngine;
+use crate::storage::StoredListState;
+use serde::Serialize;
+use std::cmp::Ordering;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    Csv,
+    Markdown,
+    Json,
+}
+
+impl ExportFormat {
+    pub const ALL: [ExportFormat; 3] = [
+        ExportFormat::Csv,
+        ExportFormat::Markdown,
+        ExportFormat::Json,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "CSV",
+            ExportFormat::Markdown => "Markdown",
+            ExportFormat::Json => "JSON",
+        }
+    }
+
+    pub fn extension(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "csv",
+            ExportFormat::Markdown => "md",
+            ExportFormat::Json => "json",
+        }
+    }
+
+    pub fn mime_type(self) -> &'static str {
+        match self {
+            ExportFormat::Csv => "text/csv",
+            ExportFormat::Markdown => "text/markdown",
+            ExportFormat::Json => "application/json",
+        }
+    }
+}
+
+/// One item of an exported ranking.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RankingRow {
+    pub rank: usize,
+    pub label: String,
+    pub id: String,
+    /// On the engine's display scale.
+    pub rating: f64,
+    /// Relative strength; abilities sum to one across the list.
+    pub ability: f64,
+    /// 95% interval around `rating`, once the engine has one.
+    pub interval: Option<(f64, f64)>,
+    /// Comparisons played, ties included.
+    pub matches: u32,
+    pub wins: u32,
+    pub losses: u32,
+}
+
+/// Items that pass the list's filter and have played, best first, as the
+/// menu lists them. Items already placed by the exact-order sort come first,
+/// in sort order; items eliminated from a top-K search sink to the bottom.
+pub fn ranked_indices(
+    items: &[ListItem],
+    state: &StoredListState,
+    engine: &dyn RatingEngine,
+) -> Vec<usize> {
+    let mut sort_positions = vec![None; items.len()];
+    if let Some(sort) = state.sort.as_ref() {
+        for (position, &index) in sort.order().iter().enumerate() {
+            if let Some(slot) = sort_positions.get_mut(index) {
+                *slot = Some(position);
+            }
+        }
+    }
+    let eliminated = |index: usize| {
+        state
+            .top_k
+            .as_ref()
+            .is_some_and(|top_k| top_k.is_eliminated(index))
+    };
+    let filter = ItemFilter::parse(&state.filter);
+    let mut indices: Vec<usize> = items
+        .iter()
+        .enumerate()
+        .filter(|(index, item)| {
+            filter.matches(item) && state.match_totals.get(*index).copied().unwrap_or(0) > 0
+        })
+        .map(|(index, _)| index)
+        .collect();
+    indices.sort_by(|&a, &b| {
+        eliminated(a).cmp(&eliminated(b)).then_with(|| {
+            match (sort_positions[a], sort_positions[b]) {
+                (Some(left), Some(right)) => left.cmp(&right),
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (None, None) => engine
+                    .display_rating(b)
+                    .partial_cmp(&engine.display_rating(a))
+                    .unwrap_or(Ordering::Equal),
+            }
+        })
+    });
+    indices
+}
+
+pub fn ranking_rows(
+    items: &[ListItem],
+    state: &StoredListState,
+    engine: &dyn RatingEngine,
+) -> Vec<RankingRow> {
+    ranked_indices(items, state, engine)
+        .into_iter()
+        .enumerate()
+        .map(|(position, index)| {
+            let item = &items[index];
+            let wins = state
+                .win_matrix
+                .get(index)
+                .map_or(0, |row| row.iter().sum());
+            let losses = state
+                .win_matrix
+                .iter()
+                .filter_map(|row| row.get(index))
+                .sum();
+            RankingRow {
+                rank: position + 1,
+                label: item.label.clone(),
+                id: item.id.clone(),
+                rating: engine.display_rating(index),
+                ability: engine.abilities().get(index).copied().unwrap_or(0.0),
+                interval: engine.rating_interval(index),
+                matches: state.match_totals.get(index).copied().unwrap_or(0),
+                wins,
+                losses,
+            }
+        })
+        .collect()
+}
+
+pub fn format_ranking(rows: &[RankingRow], format: ExportFormat) -> String {
+    match format {
+        ExportFormat::Csv => csv(rows),
+        ExportFormat::Markdown => markdown(rows),
+        ExportFormat::Json => serde_json::to_string_pretty(rows).unwrap_or_default(),
+    }
+}
+
+/// `Boy names` becomes `boy-names-ranking.csv`.
+pub fn export_file_name(title: &str, format: ExportFormat) -> String {
+    let mut stem = String::new();
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            stem.push(c);
+        } else if !stem.is_empty() && !stem.ends_with('-') {
+            stem.push('-');
+        }
+    }
+    let stem = stem.trim_end_matches('-');
+    let stem = if stem.is_empty() { "list" } else { stem };
+    format!("{}-ranking.{}", stem, format.extension())
+}
+
+fn csv(rows: &[RankingRow]) -> String {
+    let mut out = String::from(
+        "rank,label,id,rating,ability,interval_low,interval_high,matches,wins,losses\n",
+    );
+    for row in rows {
+        let (low, high) = row
+            .interval
+            .map_or((String::new(), String::new()), |(low, high)| {
+                (format!("{:.1}", low), format!("{:.1}", high))
+            });
+        out.push_str(&format!(
+            "{},{},{},{:.1},{:.6},{},{},{},{},{}\n",
+            row.rank,
+            csv_field(&row.label),
+            csv_field(&row.id),
+            row.rating,
+            row.ability,
+            low,
+            high,
+            row.matches,
+            row.wins,
+            row.losses
+        ));
+    }
+    out
+}
+
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+fn markdown(rows: &[RankingRow]) -> String {
+    let mut out = String::from(
+        "| Rank | Label | Id | Rating | Ability | Interval | Matches | Wins | Losses |\n\
+         | ---: | --- | --- | ---: | ---: | --- | ---: | ---: | ---: |\n",
+    );
+    for row in rows {
+        let interval = row.interval.map_or(String::new(), |(low, high)| {
+            format!("{:.0}–{:.0}", low, high)
+        });
+        out.push_str(&format!(
+            "| {} | {} | {} | {:.0} | {:.4} | {} | {} | {} | {} |\n",
+            row.rank,
+            markdown_cell(&row.label),
+            markdown_cell(&row.id),
+            row.rating,
+            row.ability,
+            interval,
+            row.matches,
+            row.wins,
+            row.losses
+        ));
+    }
+    out
+}
+
+fn markdown_cell(value: &str) -> String {
+    value.replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::EventStamp;
+
+    fn item(id: &str, label: &str) -> ListItem {
+        ListItem {
+            id: id.to_string(),
+            label: label.to_string(),
+            ..ListItem::default()
+        }
+    }
+
+    #[test]
+    fn rankings_export_in_every_format() {
+        let items = [
+            item("a", "Ann, \"Annie\""),
+            item("b", "Bo | Bob"),
+            item("c", "Cy"),
+        ];
+        let ids: Vec<String> = items.iter().map(|item| item.id.clone()).collect();
+        let mut state = StoredListState::new(&ids);
+        let mut engine = state.restore_engine();
+        let stamp = EventStamp::default();
+        for _ in 0..3 {
+            assert!(state.record_result(0, 1, Some(1), engine.as_mut(), &stamp));
+        }
+        assert!(state.record_result(0, 1, None, engine.as_mut(), &stamp));
+
+        let rows = ranking_rows(&items, &state, engine.as_ref());
+        assert_eq!(rows.len(), 2);
+        assert_eq!((rows[0].id.as_str(), rows[0].rank), ("b", 1));
+        assert_eq!((rows[0].matches, rows[0].wins, rows[0].losses), (4, 3, 0));
+        assert_eq!((rows[1].wins, rows[1].losses), (0, 3));
+
+        let csv = format_ranking(&rows, ExportFormat::Csv);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert!(lines[0].starts_with("rank,label,id,rating"));
+        assert!(lines[2].starts_with("2,\"Ann, \"\"Annie\"\"\",a,"));
+        assert!(format_ranking(&rows, ExportFormat::Markdown).contains("| 1 | Bo \\| Bob | b |"));
+        let json: serde_json::Value =
+            serde_json::from_str(&format_ranking(&rows, ExportFormat::Json)).unwrap();
+        assert_eq!(json[1]["label"], "Ann, \"Annie\"");
+        assert_eq!(json[0]["losses"], 0);
+
+        assert_eq!(
+            export_file_name("Boy names (UK)", ExportFormat::Markdown),
+            "boy-names-uk-ranking.md"
+        );
+        assert_eq!(
+            export_file_name("!!", ExportFormat::Csv),
+            "list-ranking.csv"
+        );
+    }
+}
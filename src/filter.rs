@@ -0,0 +1,123 @@
+use crate::data::ListItem;
+
+/// A search over item labels and tags, as typed into the menu.
+///
+/// Terms are separated by spaces and must all match; case is ignored.
+///
+/// - `#classic` or `tag:classic` keeps items tagged `classic`
+/// - `^M` or `starts:M` keeps labels starting with `M`
+/// - any other word keeps labels containing it
+/// - a leading `-` keeps the items a term does *not* match, e.g. `-#short`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ItemFilter {
+    terms: Vec<(bool, Term)>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Term {
+    Tag(String),
+    Prefix(String),
+    Contains(String),
+}
+
+impl ItemFilter {
+    pub fn parse(query: &str) -> Self {
+        let terms = query
+            .split_whitespace()
+            .filter_map(|word| {
+                let (negated, word) = match word.strip_prefix('-') {
+                    Some(rest) if !rest.is_empty() => (true, rest),
+                    _ => (false, word),
+                };
+                let word = word.to_lowercase();
+                let term = if let Some(tag) = word.strip_prefix('#').or(word.strip_prefix("tag:")) {
+                    Term::Tag(tag.to_string())
+                } else if let Some(prefix) = word.strip_prefix('^').or(word.strip_prefix("starts:"))
+                {
+                    Term::Prefix(prefix.to_string())
+                } else {
+                    Term::Contains(word)
+                };
+                let empty = match &term {
+                    Term::Tag(value) | Term::Prefix(value) | Term::Contains(value) => {
+                        value.is_empty()
+                    }
+                };
+                (!empty).then_some((negated, term))
+            })
+            .collect();
+        Self { terms }
+    }
+
+    /// True when the filter keeps every item.
+    pub fn is_empty(&self) -> bool {
+        self.terms.is_empty()
+    }
+
+    pub fn matches(&self, item: &ListItem) -> bool {
+        let label = item.label.to_lowercase();
+        self.terms.iter().all(|(negated, term)| {
+            let hit = match term {
+                Term::Tag(tag) => item.tags.iter().any(|value| value.to_lowercase() == *tag),
+                Term::Prefix(prefix) => label.starts_with(prefix.as_str()),
+                Term::Contains(text) => label.contains(text.as_str()),
+            };
+            hit != *negated
+        })
+    }
+
+    /// One flag per item, or `None` when the filter keeps everything.
+    pub fn mask(&self, items: &[ListItem]) -> Option<Vec<bool>> {
+        if self.is_empty() {
+            return None;
+        }
+        Some(items.iter().map(|item| self.matches(item)).collect())
+    }
+}
+
+/// Every distinct tag in `items`, sorted, for suggesting filters.
+pub fn list_tags(items: &[ListItem]) -> Vec<String> {
+    let mut tags: Vec<String> = items.iter().flat_map(|item| item.tags.clone()).collect();
+    tags.sort_by_key(|tag| tag.to_lowercase());
+    tags.dedup_by(|a, b| a.eq_ignore_ascii_case(b));
+    tags
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(label: &str, tags: &[&str]) -> ListItem {
+        ListItem {
+            id: label.to_lowercase(),
+            label: label.to_string(),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            ..ListItem::default()
+        }
+    }
+
+    #[test]
+    fn terms_are_combined() {
+        let items = [
+            item("Marko", &["Classic", "short"]),
+            item("Mateo", &["modern"]),
+            item("Ivan", &["classic"]),
+            item("Emma", &[]),
+        ];
+        let keep = |query: &str| ItemFilter::parse(query).mask(&items);
+
+        assert_eq!(keep("   "), None);
+        assert_eq!(keep("#classic"), Some(vec![true, false, true, false]));
+        assert_eq!(
+            keep("tag:CLASSIC ^m"),
+            Some(vec![true, false, false, false])
+        );
+        assert_eq!(
+            keep("starts:m -#short"),
+            Some(vec![false, true, false, false])
+        );
+        assert_eq!(keep("ma"), Some(vec![true, true, false, true]));
+        assert_eq!(keep("# ^ tag:"), None);
+        assert_eq!(list_tags(&items), ["Classic", "modern", "short"]);
+    }
+}
@@ -0,0 +1,307 @@
+use crate::ranking::{RatingEngine, RatingEngineKind};
+use serde::{Deserialize, Serialize};
+use std::f64::consts::PI;
+
+const GLICKO_SCALE: f64 = 173.7178;
+const DEFAULT_RATING: f64 = 1500.0;
+const DEFAULT_DEVIATION: f64 = 350.0;
+const DEFAULT_VOLATILITY: f64 = 0.06;
+const MIN_DEVIATION: f64 = 30.0;
+const TAU: f64 = 0.5;
+const VOLATILITY_EPSILON: f64 = 1e-6;
+const INTERVAL_Z: f64 = 1.96;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct GlickoRating {
+    pub rating: f64,
+    pub deviation: f64,
+    pub volatility: f64,
+}
+
+impl Default for GlickoRating {
+    fn default() -> Self {
+        Self {
+            rating: DEFAULT_RATING,
+            deviation: DEFAULT_DEVIATION,
+            volatility: DEFAULT_VOLATILITY,
+        }
+    }
+}
+
+impl GlickoRating {
+    fn mu(&self) -> f64 {
+        (self.rating - DEFAULT_RATING) / GLICKO_SCALE
+    }
+
+    fn phi(&self) -> f64 {
+        self.deviation / GLICKO_SCALE
+    }
+}
+
+/// Glicko-2 ratings updated incrementally after each comparison.
+///
+/// Every swipe is treated as a rating period containing a single game for the
+/// two items involved. Items that were not shown keep their deviation, so long
+/// lists do not drift back towards "unknown" between visits.
+#[derive(Debug, Clone)]
+pub struct Glicko2 {
+    ratings: Vec<GlickoRating>,
+    abilities: Vec<f64>,
+}
+
+impl Glicko2 {
+    pub fn new(count: usize) -> Self {
+        Self::from_ratings(vec![GlickoRating::default(); count])
+    }
+
+    pub fn from_ratings(ratings: Vec<GlickoRating>) -> Self {
+        let mut system = Self {
+            ratings,
+            abilities: Vec::new(),
+        };
+        system.refresh_abilities();
+        system
+    }
+
+    pub fn ratings(&self) -> &[GlickoRating] {
+        &self.ratings
+    }
+
+    /// Applies the outcome of a single game; `score` is 1.0 when `i` won, 0.5
+    /// for a tie and 0.0 when `j` won.
+    pub fn record_game(&mut self, i: usize, j: usize, score: f64) {
+        self.record_games([(i, j, score)]);
+    }
+
+    /// Applies `(i, j, score)` games in order, as [`Glicko2::record_game`]
+    /// would one at a time.
+    pub fn record_games(&mut self, games: impl IntoIterator<Item = (usize, usize, f64)>) {
+        for (i, j, score) in games {
+            if i == j || i >= self.ratings.len() || j >= self.ratings.len() {
+                continue;
+            }
+            let left = self.ratings[i];
+            let right = self.ratings[j];
+            self.ratings[i] = update_rating(left, &[(right, score)]);
+            self.ratings[j] = update_rating(right, &[(left, 1.0 - score)]);
+        }
+        self.refresh_abilities();
+    }
+
+    /// Rates every recorded game as one Glicko-2 rating period, starting from
+    /// default ratings. Used when no incremental history is available.
+    pub fn rebuild(&mut self, wins: &[Vec<u32>], ties: &[Vec<u32>]) {
+        let n = wins.len();
+        let start = GlickoRating::default();
+        let cell = |matrix: &[Vec<u32>], i: usize, j: usize| {
+            matrix
+                .get(i)
+                .and_then(|row| row.get(j))
+                .copied()
+                .unwrap_or(0)
+        };
+
+        self.ratings = (0..n)
+            .map(|i| {
+                let mut games = Vec::new();
+                for j in 0..n {
+                    if i == j {
+                        continue;
+                    }
+                    games.extend((0..cell(wins, i, j)).map(|_| (start, 1.0)));
+                    games.extend((0..cell(wins, j, i)).map(|_| (start, 0.0)));
+                    games.extend((0..cell(ties, i, j)).map(|_| (start, 0.5)));
+                }
+                update_rating(start, &games)
+            })
+            .collect();
+        self.refresh_abilities();
+    }
+
+    fn refresh_abilities(&mut self) {
+        let strengths: Vec<f64> = self.ratings.iter().map(|r| r.mu().exp()).collect();
+        let sum: f64 = strengths.iter().sum();
+        self.abilities = if sum > 0.0 {
+            strengths.into_iter().map(|value| value / sum).collect()
+        } else {
+            strengths
+        };
+    }
+}
+
+impl RatingEngine for Glicko2 {
+    fn kind(&self) -> RatingEngineKind {
+        RatingEngineKind::Glicko2
+    }
+
+    fn ensure_len(&mut self, len: usize) {
+        if self.ratings.len() != len {
+            self.ratings.resize(len, GlickoRating::default());
+            self.refresh_abilities();
+        }
+    }
+
+    fn abilities(&self) -> &[f64] {
+        &self.abilities
+    }
+
+    fn rebuild(&mut self, wins: &[Vec<u32>], ties: &[Vec<u32>]) {
+        Glicko2::rebuild(self, wins, ties);
+    }
+
+    fn record(&mut self, i: usize, j: usize, score: f64, _wins: &[Vec<u32>], _ties: &[Vec<u32>]) {
+        self.record_game(i, j, score);
+    }
+
+    fn display_rating(&self, index: usize) -> f64 {
+        self.ratings
+            .get(index)
+            .map(|r| r.rating)
+            .unwrap_or(DEFAULT_RATING)
+            .max(0.0)
+    }
+
+    fn display_scale(&self) -> f64 {
+        GLICKO_SCALE
+    }
+
+    fn rating_margin(&self, index: usize) -> Option<f64> {
+        self.ratings.get(index).map(|r| INTERVAL_Z * r.deviation)
+    }
+
+    fn glicko_ratings(&self) -> Option<&[GlickoRating]> {
+        Some(&self.ratings)
+    }
+
+    fn clone_box(&self) -> Box<dyn RatingEngine> {
+        Box::new(self.clone())
+    }
+}
+
+fn g(phi: f64) -> f64 {
+    1.0 / (1.0 + 3.0 * phi * phi / (PI * PI)).sqrt()
+}
+
+fn expected(mu: f64, opponent_mu: f64, opponent_phi: f64) -> f64 {
+    1.0 / (1.0 + (-g(opponent_phi) * (mu - opponent_mu)).exp())
+}
+
+/// One Glicko-2 rating-period update for `player` against `games`, each given
+/// as `(opponent, score)`.
+fn update_rating(player: GlickoRating, games: &[(GlickoRating, f64)]) -> GlickoRating {
+    let mu = player.mu();
+    let phi = player.phi();
+    let sigma = player.volatility;
+
+    if games.is_empty() {
+        return player;
+    }
+
+    let mut inverse_variance = 0.0;
+    let mut score_sum = 0.0;
+    for (opponent, score) in games {
+        let g_phi = g(opponent.phi());
+        let e = expected(mu, opponent.mu(), opponent.phi());
+        inverse_variance += g_phi * g_phi * e * (1.0 - e);
+        score_sum += g_phi * (score - e);
+    }
+    if inverse_variance <= 0.0 {
+        return player;
+    }
+    let variance = 1.0 / inverse_variance;
+    let delta = variance * score_sum;
+
+    let new_sigma = updated_volatility(phi, sigma, variance, delta);
+    let phi_star = (phi * phi + new_sigma * new_sigma).sqrt();
+    let new_phi = 1.0 / (1.0 / (phi_star * phi_star) + 1.0 / variance).sqrt();
+    let new_mu = mu + new_phi * new_phi * score_sum;
+
+    GlickoRating {
+        rating: DEFAULT_RATING + GLICKO_SCALE * new_mu,
+        deviation: (GLICKO_SCALE * new_phi).clamp(MIN_DEVIATION, DEFAULT_DEVIATION),
+        volatility: new_sigma,
+    }
+}
+
+/// Solves for the new volatility with the Illinois variant of regula falsi,
+/// as described in step 5 of Glickman's Glicko-2 paper.
+fn updated_volatility(phi: f64, sigma: f64, variance: f64, delta: f64) -> f64 {
+    let a = (sigma * sigma).ln();
+    let phi2 = phi * phi;
+    let f = |x: f64| {
+        let ex = x.exp();
+        let denom = phi2 + variance + ex;
+        ex * (delta * delta - phi2 - variance - ex) / (2.0 * denom * denom) - (x - a) / (TAU * TAU)
+    };
+
+    let mut bound_a = a;
+    let mut bound_b = if delta * delta > phi2 + variance {
+        (delta * delta - phi2 - variance).ln()
+    } else {
+        let mut k = 1.0;
+        while f(a - k * TAU) < 0.0 {
+            k += 1.0;
+        }
+        a - k * TAU
+    };
+
+    let mut f_a = f(bound_a);
+    let mut f_b = f(bound_b);
+    let mut guard = 0;
+    while (bound_b - bound_a).abs() > VOLATILITY_EPSILON && guard < 100 {
+        let c = bound_a + (bound_a - bound_b) * f_a / (f_b - f_a);
+        let f_c = f(c);
+        if f_c * f_b <= 0.0 {
+            bound_a = bound_b;
+            f_a = f_b;
+        } else {
+            f_a /= 2.0;
+        }
+        bound_b = c;
+        f_b = f_c;
+        guard += 1;
+    }
+
+    (bound_a / 2.0).exp()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matches_glickman_example() {
+        let player = GlickoRating {
+            rating: 1500.0,
+            deviation: 200.0,
+            volatility: 0.06,
+        };
+        let opponent = |rating, deviation| GlickoRating {
+            rating,
+            deviation,
+            volatility: 0.06,
+        };
+        let games = [
+            (opponent(1400.0, 30.0), 1.0),
+            (opponent(1550.0, 100.0), 0.0),
+            (opponent(1700.0, 300.0), 0.0),
+        ];
+
+        let updated = update_rating(player, &games);
+
+        assert!((updated.rating - 1464.06).abs() < 0.1);
+        assert!((updated.deviation - 151.52).abs() < 0.1);
+        assert!((updated.volatility - 0.05999).abs() < 1e-4);
+    }
+
+    #[test]
+    fn winner_gains_and_deviation_shrinks() {
+        let mut system = Glicko2::new(2);
+        system.record_game(0, 1, 1.0);
+
+        let ratings = system.ratings();
+        assert!(ratings[0].rating > ratings[1].rating);
+        assert!(ratings[0].deviation < DEFAULT_DEVIATION);
+        assert!(system.abilities()[0] > system.abilities()[1]);
+    }
+}
@@ -0,0 +1,170 @@
+use crate::matchflow::MatchupMode;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// One recorded comparison. A list's events are its source of truth; the
+/// win, tie and total counts are derived from them with [`tally`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MatchEvent {
+    /// Milliseconds since the Unix epoch; 0 when the time is not known, as for
+    /// outcomes saved before events were kept.
+    #[serde(default)]
+    pub at: u64,
+    /// For a tie, the item that was shown on the left.
+    pub winner: String,
+    pub loser: String,
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub tie: bool,
+    /// Matchup mode that picked the pair.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub strategy: Option<MatchupMode>,
+    /// App session the decision was made in.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub session: String,
+    /// The list the comparison was made in when it was copied over from
+    /// another list; `None` for the list's own decisions.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Copied to the other lists with both items when it was made, so edits
+    /// to it have to be passed on too.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub shared: bool,
+}
+
+/// When and where a decision is made.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EventStamp {
+    pub at: u64,
+    pub session: String,
+}
+
+impl MatchEvent {
+    /// An outcome between `left` and `right`; `winner` is `None` for a tie.
+    pub fn new(left: &str, right: &str, winner: Option<&str>) -> Self {
+        let (first, second) = match winner {
+            Some(winner) if winner == right => (right, left),
+            _ => (left, right),
+        };
+        Self {
+            at: 0,
+            winner: first.to_string(),
+            loser: second.to_string(),
+            tie: winner.is_none(),
+            strategy: None,
+            session: String::new(),
+            source: None,
+            shared: false,
+        }
+    }
+
+    pub fn stamped(mut self, stamp: &EventStamp) -> Self {
+        self.at = stamp.at;
+        self.session = stamp.session.clone();
+        self
+    }
+
+    /// The winning item, or `None` for a tie.
+    pub fn winner_id(&self) -> Option<&str> {
+        (!self.tie).then_some(self.winner.as_str())
+    }
+
+    /// True when both events record the same result for the same pair.
+    pub fn same_outcome(&self, other: &MatchEvent) -> bool {
+        if self.tie || other.tie {
+            self.tie == other.tie
+                && ((self.winner == other.winner && self.loser == other.loser)
+                    || (self.winner == other.loser && self.loser == other.winner))
+        } else {
+            self.winner == other.winner && self.loser == other.loser
+        }
+    }
+}
+
+/// Counts derived from a run of events.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tally {
+    /// `wins[i][j]` is how often item `i` beat item `j`.
+    pub wins: Vec<Vec<u32>>,
+    /// Symmetric tie counts.
+    pub ties: Vec<Vec<u32>>,
+    /// Comparisons per item, ties included.
+    pub totals: Vec<u32>,
+}
+
+/// Replays `events` onto the items `item_ids`. Events naming an item that is
+/// no longer in the list stay in the history but are not counted. Passing a
+/// prefix of the log gives the counts as they were at that point.
+pub fn tally(events: &[MatchEvent], item_ids: &[String]) -> Tally {
+    let count = item_ids.len();
+    let positions: HashMap<&str, usize> = item_ids
+        .iter()
+        .enumerate()
+        .map(|(index, id)| (id.as_str(), index))
+        .collect();
+    let mut tally = Tally {
+        wins: vec![vec![0; count]; count],
+        ties: vec![vec![0; count]; count],
+        totals: vec![0; count],
+    };
+    for event in events {
+        let (Some(&i), Some(&j)) = (
+            positions.get(event.winner.as_str()),
+            positions.get(event.loser.as_str()),
+        ) else {
+            continue;
+        };
+        if i == j {
+            continue;
+        }
+        if event.tie {
+            tally.ties[i][j] = tally.ties[i][j].saturating_add(1);
+            tally.ties[j][i] = tally.ties[j][i].saturating_add(1);
+        } else {
+            tally.wins[i][j] = tally.wins[i][j].saturating_add(1);
+        }
+        for index in [i, j] {
+            tally.totals[index] = tally.totals[index].saturating_add(1);
+        }
+    }
+    tally
+}
+
+/// A short random id that tells one app session's decisions from another's.
+pub fn new_session_id(rng: &mut dyn RngCore) -> String {
+    format!("{:08x}", rng.gen::<u32>())
+}
+
+fn is_false(value: &bool) -> bool {
+    !*value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    #[test]
+    fn tally_replays_wins_and_ties() {
+        let events = [
+            MatchEvent::new("a", "b", Some("a")),
+            MatchEvent::new("a", "b", Some("b")),
+            MatchEvent::new("c", "a", None),
+            MatchEvent::new("a", "gone", Some("a")),
+        ];
+        let counts = tally(&events, &ids(&["a", "b", "c"]));
+
+        assert_eq!(counts.wins, [[0, 1, 0], [1, 0, 0], [0, 0, 0]]);
+        assert_eq!(counts.ties[0][2], 1);
+        assert_eq!(counts.ties[2][0], 1);
+        assert_eq!(counts.totals, [3, 2, 1]);
+        assert_eq!(tally(&events[..1], &ids(&["a", "b"])).totals, [1, 1]);
+
+        assert_eq!(events[2].winner_id(), None);
+        assert!(events[2].same_outcome(&MatchEvent::new("a", "c", None)));
+        assert!(!events[0].same_outcome(&events[1]));
+    }
+}
@@ -0,0 +1,377 @@
+use crate::data::{list_from_items, DataError, ListItem, LoadedList};
+use std::collections::HashMap;
+
+/// Separator for several tags in one cell, e.g. `classic; short`.
+const TAG_SEPARATOR: char = ';';
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImportFormat {
+    Csv,
+    Tsv,
+    Text,
+}
+
+impl ImportFormat {
+    pub const ALL: [ImportFormat; 3] = [ImportFormat::Csv, ImportFormat::Tsv, ImportFormat::Text];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ImportFormat::Csv => "CSV",
+            ImportFormat::Tsv => "TSV",
+            ImportFormat::Text => "Text",
+        }
+    }
+
+    /// Guesses the format from a file extension; anything unknown is read as
+    /// one label per line.
+    pub fn from_file_name(name: &str) -> Self {
+        let extension = name
+            .rsplit_once('.')
+            .map(|(_, extension)| extension.to_ascii_lowercase());
+        match extension.as_deref() {
+            Some("csv") => ImportFormat::Csv,
+            Some("tsv") | Some("tab") => ImportFormat::Tsv,
+            _ => ImportFormat::Text,
+        }
+    }
+
+    fn delimiter(self) -> Option<char> {
+        match self {
+            ImportFormat::Csv => Some(','),
+            ImportFormat::Tsv => Some('\t'),
+            ImportFormat::Text => None,
+        }
+    }
+}
+
+/// Which columns of a table hold what. Columns are zero-based; plain text
+/// files only have column 0.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ImportOptions {
+    pub format: ImportFormat,
+    /// The first row names the columns instead of holding an item.
+    pub has_header: bool,
+    pub label_column: usize,
+    pub id_column: Option<usize>,
+    pub tags_column: Option<usize>,
+}
+
+impl Default for ImportOptions {
+    fn default() -> Self {
+        Self {
+            format: ImportFormat::Text,
+            has_header: false,
+            label_column: 0,
+            id_column: None,
+            tags_column: None,
+        }
+    }
+}
+
+impl ImportOptions {
+    /// Options for a freshly picked file. Tables are assumed to have a header
+    /// row, and columns called `label`/`name`/`title`, `id` and `tags` are
+    /// chosen when present.
+    pub fn detect(format: ImportFormat, text: &str) -> Self {
+        let mut options = Self {
+            format,
+            ..Self::default()
+        };
+        if format == ImportFormat::Text {
+            return options;
+        }
+
+        options.has_header = true;
+        let header: Vec<String> = column_names(text, &options)
+            .iter()
+            .map(|name| name.trim().to_lowercase())
+            .collect();
+        let find = |names: &[&str]| {
+            header
+                .iter()
+                .position(|name| names.contains(&name.as_str()))
+        };
+        options.label_column = find(&["label", "name", "title", "item"]).unwrap_or(0);
+        options.id_column = find(&["id", "key", "slug"]);
+        options.tags_column = find(&["tags", "tag", "category", "categories"]);
+        options
+    }
+}
+
+/// Names for the columns of `text`: the header cells, or `Column N` when the
+/// file has no header row.
+pub fn column_names(text: &str, options: &ImportOptions) -> Vec<String> {
+    let Ok(records) = read_records(text, options.format) else {
+        return Vec::new();
+    };
+    let Some(first) = records.first() else {
+        return Vec::new();
+    };
+    if options.has_header {
+        first
+            .fields
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let name = name.trim();
+                if name.is_empty() {
+                    format!("Column {}", index + 1)
+                } else {
+                    name.to_string()
+                }
+            })
+            .collect()
+    } else {
+        let width = records
+            .iter()
+            .map(|record| record.fields.len())
+            .max()
+            .unwrap_or(0);
+        (1..=width)
+            .map(|index| format!("Column {}", index))
+            .collect()
+    }
+}
+
+/// Reads a CSV, TSV or plain-text file into a list. Blank rows are skipped.
+/// Every row that cannot become an item is reported as its own
+/// `DataError::Parse` with the line it starts on, so a spreadsheet can be
+/// fixed in one pass.
+pub fn import_list(
+    list_id: &str,
+    title: &str,
+    text: &str,
+    options: &ImportOptions,
+) -> Result<LoadedList, Vec<DataError>> {
+    let records = read_records(text, options.format).map_err(|err| vec![err])?;
+    let skip = usize::from(options.has_header && options.format != ImportFormat::Text);
+
+    let mut items = Vec::new();
+    let mut errors = Vec::new();
+    let mut id_lines: HashMap<String, usize> = HashMap::new();
+    for record in records.into_iter().skip(skip) {
+        let cell = |column: Option<usize>| {
+            column
+                .and_then(|column| record.fields.get(column))
+                .map(|value| value.trim())
+                .unwrap_or("")
+        };
+
+        let Some(label) = record.fields.get(options.label_column) else {
+            errors.push(row_error(
+                record.line,
+                format!("no column {}", options.label_column + 1),
+            ));
+            continue;
+        };
+        let label = label.trim();
+        if label.is_empty() {
+            errors.push(row_error(record.line, "the label is empty"));
+            continue;
+        }
+
+        let id = cell(options.id_column);
+        if !id.is_empty() {
+            if let Some(first_line) = id_lines.get(id) {
+                errors.push(row_error(
+                    record.line,
+                    format!("id '{}' is already used on line {}", id, first_line),
+                ));
+                continue;
+            }
+            id_lines.insert(id.to_string(), record.line);
+        }
+
+        items.push(ListItem {
+            id: id.to_string(),
+            label: label.to_string(),
+            tags: cell(options.tags_column)
+                .split(TAG_SEPARATOR)
+                .map(str::trim)
+                .filter(|tag| !tag.is_empty())
+                .map(str::to_string)
+                .collect(),
+            ..ListItem::default()
+        });
+    }
+
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+    let title = Some(title.trim().to_string()).filter(|title| !title.is_empty());
+    list_from_items(list_id, title, items).map_err(|err| vec![err])
+}
+
+/// A list title from a file name: the name without its extension.
+pub fn title_from_file_name(name: &str) -> String {
+    let stem = match name.rsplit_once('.') {
+        Some((stem, _)) if !stem.is_empty() => stem,
+        _ => name,
+    };
+    stem.replace(['_', '-'], " ").trim().to_string()
+}
+
+fn row_error(line: usize, message: impl std::fmt::Display) -> DataError {
+    DataError::Parse(format!("Line {}: {}", line, message))
+}
+
+#[derive(Debug)]
+struct Record {
+    /// 1-based line the record starts on.
+    line: usize,
+    fields: Vec<String>,
+}
+
+fn read_records(text: &str, format: ImportFormat) -> Result<Vec<Record>, DataError> {
+    let text = text.strip_prefix('\u{feff}').unwrap_or(text);
+    let records = match format.delimiter() {
+        Some(delimiter) => read_delimited(text, delimiter)?,
+        None => text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| Record {
+                line: index + 1,
+                fields: vec![line.to_string()],
+            })
+            .collect(),
+    };
+    Ok(records
+        .into_iter()
+        .filter(|record| record.fields.iter().any(|field| !field.trim().is_empty()))
+        .collect())
+}
+
+/// RFC 4180 style parsing: fields may be quoted, quoted fields may contain
+/// the delimiter, line breaks and doubled quotes.
+fn read_delimited(text: &str, delimiter: char) -> Result<Vec<Record>, DataError> {
+    let mut records = Vec::new();
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut in_quotes = false;
+    let mut line = 1;
+    let mut record_line = 1;
+
+    let mut chars = text.chars().peekable();
+    while let Some(ch) = chars.next() {
+        if in_quotes {
+            match ch {
+                '"' if chars.peek() == Some(&'"') => {
+                    chars.next();
+                    field.push('"');
+                }
+                '"' => in_quotes = false,
+                '\n' => {
+                    line += 1;
+                    field.push(ch);
+                }
+                _ => field.push(ch),
+            }
+            continue;
+        }
+
+        match ch {
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                in_quotes = true;
+            }
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                fields.push(std::mem::take(&mut field));
+                records.push(Record {
+                    line: record_line,
+                    fields: std::mem::take(&mut fields),
+                });
+                line += 1;
+                record_line = line;
+            }
+            _ if ch == delimiter => fields.push(std::mem::take(&mut field)),
+            _ => field.push(ch),
+        }
+    }
+
+    if in_quotes {
+        return Err(row_error(record_line, "a quoted field is never closed"));
+    }
+    if !field.is_empty() || !fields.is_empty() {
+        fields.push(field);
+        records.push(Record {
+            line: record_line,
+            fields,
+        });
+    }
+    Ok(records)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn messages(errors: Vec<DataError>) -> Vec<String> {
+        errors.into_iter().map(|err| err.to_string()).collect()
+    }
+
+    #[test]
+    fn csv_uses_the_chosen_columns() {
+        let text =
+            "id,Name,Tags\r\nciro,\"Ćiro, Jr.\",classic; short\n,Ivo,\n\n,\"Multi\nline\",x\n";
+        let options = ImportOptions::detect(ImportFormat::Csv, text);
+        assert_eq!(options.label_column, 1);
+        assert_eq!(options.id_column, Some(0));
+        assert_eq!(options.tags_column, Some(2));
+
+        let list = import_list("custom:names", "Names", text, &options).unwrap();
+        assert_eq!(list.info.label, "Names");
+        assert_eq!(list.items.len(), 3);
+        assert_eq!(list.items[0].id, "ciro");
+        assert_eq!(list.items[0].label, "Ćiro, Jr.");
+        assert_eq!(list.items[0].tags, ["classic", "short"]);
+        assert_eq!(list.items[1].id, "ivo");
+        assert_eq!(list.items[2].label, "Multi\nline");
+    }
+
+    #[test]
+    fn row_errors_carry_line_numbers() {
+        let text = "label\tid\nApple\ta\n\t\nPear\na\tb\nPlum\ta\n";
+        let options = ImportOptions {
+            format: ImportFormat::Tsv,
+            has_header: true,
+            label_column: 0,
+            id_column: Some(1),
+            tags_column: None,
+        };
+        let errors = messages(import_list("x", "", text, &options).unwrap_err());
+        assert_eq!(
+            errors,
+            ["Invalid data: Line 6: id 'a' is already used on line 2"]
+        );
+
+        let text = "Name,Notes\n\"Open,x\n";
+        let options = ImportOptions::detect(ImportFormat::Csv, text);
+        let errors = messages(import_list("x", "", text, &options).unwrap_err());
+        assert_eq!(
+            errors,
+            ["Invalid data: Line 2: a quoted field is never closed"]
+        );
+
+        let text = "Name,Notes\n,empty\nKiwi\n";
+        let options = ImportOptions {
+            label_column: 1,
+            ..ImportOptions::detect(ImportFormat::Csv, text)
+        };
+        let errors = messages(import_list("x", "", text, &options).unwrap_err());
+        assert_eq!(errors, ["Invalid data: Line 3: no column 2"]);
+    }
+
+    #[test]
+    fn text_files_have_one_label_per_line() {
+        let text = "\u{feff}Apple\n\n  Pear  \r\nApple\n";
+        let options = ImportOptions::detect(ImportFormat::from_file_name("fruit.txt"), text);
+        let list = import_list("x", &title_from_file_name("my_fruit.txt"), text, &options).unwrap();
+
+        let labels: Vec<&str> = list.items.iter().map(|item| item.label.as_str()).collect();
+        assert_eq!(labels, ["Apple", "Pear", "Apple"]);
+        assert_eq!(list.items[2].id, "apple-2");
+        assert_eq!(list.info.label, "my fruit");
+        assert!(import_list("x", "", "\n \n", &options).is_err());
+    }
+}
@@ -1,45 +1,687 @@
+use crate::ranking::{RatingEngine, INTERVAL_Z};
 use rand::distributions::{Distribution, WeightedIndex};
-use rand::Rng;
+use rand::{Rng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Matchup {
     pub left_index: usize,
     pub right_index: usize,
 }
 
+impl Matchup {
+    /// True when this matchup compares `i` and `j`, in either order.
+    pub fn is_pair(&self, i: usize, j: usize) -> bool {
+        (self.left_index == i && self.right_index == j)
+            || (self.left_index == j && self.right_index == i)
+    }
+}
+
+/// Everything a strategy may look at when choosing the next pair.
+///
+/// Ratings and intervals are on the engine's display scale; `log_strengths`
+/// and `deviations` are the same numbers in log-odds units. When `allowed` is
+/// set, only items flagged `true` may be paired; everything else still counts
+/// as evidence.
+#[derive(Debug, Clone)]
+pub struct MatchupContext<'a> {
+    pub abilities: &'a [f64],
+    pub win_matrix: &'a [Vec<u32>],
+    pub tie_matrix: &'a [Vec<u32>],
+    pub match_totals: &'a [u32],
+    pub ratings: Vec<f64>,
+    pub intervals: Vec<Option<(f64, f64)>>,
+    pub log_strengths: Vec<f64>,
+    pub deviations: Vec<f64>,
+    pub allowed: Option<Vec<bool>>,
+}
+
+impl<'a> MatchupContext<'a> {
+    pub fn new(
+        engine: &'a dyn RatingEngine,
+        win_matrix: &'a [Vec<u32>],
+        tie_matrix: &'a [Vec<u32>],
+        match_totals: &'a [u32],
+    ) -> Self {
+        let count = engine.abilities().len();
+        let scale = engine.display_scale();
+        let ratings: Vec<f64> = (0..count)
+            .map(|index| engine.display_rating(index))
+            .collect();
+        let log_strengths = ratings.iter().map(|rating| rating / scale).collect();
+        let deviations = (0..count)
+            .map(|index| {
+                engine
+                    .rating_margin(index)
+                    .map(|margin| margin / (INTERVAL_Z * scale))
+                    .unwrap_or(PRIOR_LOG_STRENGTH_DEVIATION)
+            })
+            .collect();
+
+        Self {
+            abilities: engine.abilities(),
+            win_matrix,
+            tie_matrix,
+            match_totals,
+            ratings,
+            intervals: engine.rating_intervals(),
+            log_strengths,
+            deviations,
+            allowed: None,
+        }
+    }
+
+    /// Restricts pairing to the items flagged in `allowed`.
+    pub fn with_allowed(mut self, allowed: Option<Vec<bool>>) -> Self {
+        self.allowed = allowed;
+        self
+    }
+
+    pub fn is_allowed(&self, index: usize) -> bool {
+        match &self.allowed {
+            Some(allowed) => allowed.get(index).copied().unwrap_or(false),
+            None => true,
+        }
+    }
+}
+
+/// Chooses the next pair to show.
+///
+/// `history` holds the most recent matchups, oldest first. All randomness must
+/// come from `rng` so that a seeded generator gives reproducible sessions.
+pub trait MatchupStrategy {
+    fn next_matchup(
+        &self,
+        context: &MatchupContext,
+        history: &[Matchup],
+        rng: &mut dyn RngCore,
+    ) -> Option<Matchup>;
+}
+
+/// Weighted random pairs biased towards close, under-played items.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AdaptiveStrategy;
+
+impl MatchupStrategy for AdaptiveStrategy {
+    fn next_matchup(
+        &self,
+        context: &MatchupContext,
+        history: &[Matchup],
+        rng: &mut dyn RngCore,
+    ) -> Option<Matchup> {
+        adaptive_matchup(
+            context.abilities,
+            context.win_matrix,
+            context.tie_matrix,
+            context.match_totals,
+            context.allowed.as_deref(),
+            history.last(),
+            rng,
+        )
+    }
+}
+
+/// Active learning over a Gaussian approximation of the ratings.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct InformationGainStrategy;
+
+impl MatchupStrategy for InformationGainStrategy {
+    fn next_matchup(
+        &self,
+        context: &MatchupContext,
+        history: &[Matchup],
+        rng: &mut dyn RngCore,
+    ) -> Option<Matchup> {
+        information_gain_matchup(
+            &context.log_strengths,
+            &context.deviations,
+            context.allowed.as_deref(),
+            history.last(),
+            rng,
+        )
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchupMode {
+    /// Weighted random pairs biased towards close, under-played items.
+    #[default]
+    Adaptive,
+    /// Binary-insertion sort that ends with a complete order.
+    ExactOrder,
+    /// Successive elimination that only refines the best `k` items.
+    TopK,
+    /// Active learning: the pair whose outcome is expected to tell us most.
+    InformationGain,
+}
+
+impl MatchupMode {
+    pub const ALL: [MatchupMode; 4] = [
+        MatchupMode::Adaptive,
+        MatchupMode::ExactOrder,
+        MatchupMode::TopK,
+        MatchupMode::InformationGain,
+    ];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            MatchupMode::Adaptive => "Adaptive",
+            MatchupMode::ExactOrder => "Exact order",
+            MatchupMode::TopK => "Top K",
+            MatchupMode::InformationGain => "Most informative",
+        }
+    }
+}
+
+/// Resumable binary-insertion sort.
+///
+/// Items are inserted one at a time into `order` (best first) by binary search,
+/// which needs at most `ceil(log2(k + 1))` comparisons for the k-th insertion.
+/// The whole struct is serialized so a half-finished sort survives reloads.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InsertionSort {
+    order: Vec<usize>,
+    pending: Vec<usize>,
+    low: usize,
+    high: usize,
+}
+
+impl InsertionSort {
+    pub fn new(count: usize) -> Self {
+        let mut indices: Vec<usize> = (0..count).collect();
+        let pending = indices.split_off(count.min(1));
+        Self {
+            low: 0,
+            high: indices.len(),
+            order: indices,
+            pending,
+        }
+    }
+
+    /// Items placed so far, best first.
+    pub fn order(&self) -> &[usize] {
+        &self.order
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    pub fn next_matchup(&self) -> Option<Matchup> {
+        let candidate = *self.pending.first()?;
+        let pivot = self.order[self.pivot_position()];
+        // Alternate sides so the item being inserted is not always on the left.
+        if (self.pending.len() + self.low + self.high).is_multiple_of(2) {
+            Some(Matchup {
+                left_index: candidate,
+                right_index: pivot,
+            })
+        } else {
+            Some(Matchup {
+                left_index: pivot,
+                right_index: candidate,
+            })
+        }
+    }
+
+    /// Applies a decision. `winner` is `None` for a tie, which places the
+    /// candidate directly below the item it was compared with.
+    pub fn record(&mut self, left_index: usize, right_index: usize, winner: Option<usize>) {
+        let Some(&candidate) = self.pending.first() else {
+            return;
+        };
+        let position = self.pivot_position();
+        let pivot = self.order[position];
+        if !((left_index == candidate && right_index == pivot)
+            || (left_index == pivot && right_index == candidate))
+        {
+            return;
+        }
+
+        match winner {
+            Some(winner) if winner == candidate => self.high = position,
+            Some(_) => self.low = position + 1,
+            None => {
+                self.low = position + 1;
+                self.high = position + 1;
+            }
+        }
+
+        if self.low >= self.high {
+            self.order.insert(self.low, candidate);
+            self.pending.remove(0);
+            self.low = 0;
+            self.high = self.order.len();
+        }
+    }
+
+    /// Upper bound on the comparisons still needed to finish the sort.
+    pub fn comparisons_remaining(&self) -> usize {
+        if self.pending.is_empty() {
+            return 0;
+        }
+        let current = ceil_log2(self.high - self.low + 1);
+        let placed = self.order.len();
+        current
+            + (1..self.pending.len())
+                .map(|offset| ceil_log2(placed + offset + 1))
+                .sum::<usize>()
+    }
+
+    /// Carries the sort over to a new item layout. Known items keep their
+    /// relative order; new items are queued for insertion.
+    pub fn remap(&self, old_ids: &[String], new_ids: &[String]) -> Self {
+        let positions: HashMap<&str, usize> = new_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.as_str(), index))
+            .collect();
+        let translate = |index: &usize| {
+            old_ids
+                .get(*index)
+                .and_then(|id| positions.get(id.as_str()))
+                .copied()
+        };
+
+        let mut order: Vec<usize> = self.order.iter().filter_map(translate).collect();
+        let mut pending: Vec<usize> = self.pending.iter().filter_map(translate).collect();
+        for index in 0..new_ids.len() {
+            if !order.contains(&index) && !pending.contains(&index) {
+                pending.push(index);
+            }
+        }
+        if order.is_empty() && !pending.is_empty() {
+            order.push(pending.remove(0));
+        }
+
+        Self {
+            low: 0,
+            high: order.len(),
+            order,
+            pending,
+        }
+    }
+
+    fn pivot_position(&self) -> usize {
+        (self.low + self.high) / 2
+    }
+}
+
+impl MatchupStrategy for InsertionSort {
+    /// While a filter hides the item being inserted or its pivot, the sort
+    /// waits and the most informative pair among the allowed items is shown.
+    fn next_matchup(
+        &self,
+        context: &MatchupContext,
+        history: &[Matchup],
+        rng: &mut dyn RngCore,
+    ) -> Option<Matchup> {
+        let matchup = InsertionSort::next_matchup(self)?;
+        if context.is_allowed(matchup.left_index) && context.is_allowed(matchup.right_index) {
+            Some(matchup)
+        } else {
+            InformationGainStrategy.next_matchup(context, history, rng)
+        }
+    }
+}
+
+/// Picks the pair whose outcome is expected to tell us the most about the
+/// ratings.
+///
+/// `means` and `deviations` describe a Gaussian approximation of the posterior
+/// over log-strengths. The expected information gain of a comparison is the
+/// mutual information between its outcome and the strengths; with one Laplace
+/// step per outcome it is `0.5 * ln(1 + p(1 - p) * v)`, where `v` is the
+/// variance of the strength difference and `p` the predicted win probability.
+/// Every allowed pair is scored.
+fn information_gain_matchup(
+    means: &[f64],
+    deviations: &[f64],
+    allowed: Option<&[bool]>,
+    last: Option<&Matchup>,
+    rng: &mut dyn RngCore,
+) -> Option<Matchup> {
+    let count = means.len().min(deviations.len());
+    let candidates = allowed_indices(count, allowed);
+    if candidates.len() < 2 {
+        return None;
+    }
+
+    let mut best: Option<(usize, usize)> = None;
+    let mut best_gain = f64::NEG_INFINITY;
+    let mut ties_seen = 0u32;
+    for (position, &i) in candidates.iter().enumerate() {
+        for &j in &candidates[position + 1..] {
+            let mut gain = expected_information_gain(
+                means[i] - means[j],
+                deviations[i].powi(2) + deviations[j].powi(2),
+            );
+            if last.is_some_and(|previous| previous.is_pair(i, j)) {
+                gain *= RECENT_PAIR_PENALTY;
+            }
+            if gain > best_gain + GAIN_TIE_EPSILON {
+                best_gain = gain;
+                best = Some((i, j));
+                ties_seen = 1;
+            } else if (gain - best_gain).abs() <= GAIN_TIE_EPSILON {
+                // Reservoir sampling keeps the choice uniform among equal gains.
+                ties_seen += 1;
+                if rng.gen_range(0..ties_seen) == 0 {
+                    best = Some((i, j));
+                }
+            }
+        }
+    }
+
+    let (first, second) = best?;
+    let (left_index, right_index) = if rng.gen_bool(0.5) {
+        (first, second)
+    } else {
+        (second, first)
+    };
+    Some(Matchup {
+        left_index,
+        right_index,
+    })
+}
+
+/// Expected information gain, in nats, from comparing two items whose
+/// log-strengths differ by `difference` with variance `variance`.
+fn expected_information_gain(difference: f64, variance: f64) -> f64 {
+    if variance <= 0.0 {
+        return 0.0;
+    }
+    // Probit approximation of the logistic integrated over the uncertainty.
+    let scaled = difference / (1.0 + std::f64::consts::PI * variance / 8.0).sqrt();
+    let win_probability = 1.0 / (1.0 + (-scaled).exp());
+    let information = win_probability * (1.0 - win_probability);
+    0.5 * (information * variance).ln_1p()
+}
+
+pub const DEFAULT_TOP_K: usize = 10;
+
+/// Successive-elimination state for identifying the best `k` items.
+///
+/// Every item carries a confidence interval from the rating engine. An item
+/// whose upper bound falls below the k-th best lower bound can no longer make
+/// the top `k` and is retired for good; items whose lower bound clears the
+/// (k+1)-th best upper bound are settled. Comparisons are only spent on the
+/// unsettled contenders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(from = "SavedTopK", into = "SavedTopK")]
+pub struct TopKState {
+    k: usize,
+    /// `eliminated[i]` is set once item `i` is retired; items past the end
+    /// are still in play.
+    eliminated: Vec<bool>,
+    eliminated_count: usize,
+}
+
+/// Saved form of [`TopKState`], listing the retired items by index.
+#[derive(Serialize, Deserialize)]
+struct SavedTopK {
+    k: usize,
+    eliminated: Vec<usize>,
+}
+
+impl From<SavedTopK> for TopKState {
+    fn from(saved: SavedTopK) -> Self {
+        let mut state = TopKState::new(saved.k);
+        for index in saved.eliminated {
+            state.eliminate(index);
+        }
+        state
+    }
+}
+
+impl From<TopKState> for SavedTopK {
+    fn from(state: TopKState) -> Self {
+        Self {
+            k: state.k,
+            eliminated: state.eliminated_indices().collect(),
+        }
+    }
+}
+
+impl TopKState {
+    pub fn new(k: usize) -> Self {
+        Self {
+            k: k.max(1),
+            eliminated: Vec::new(),
+            eliminated_count: 0,
+        }
+    }
+
+    pub fn k(&self) -> usize {
+        self.k
+    }
+
+    pub fn is_eliminated(&self, index: usize) -> bool {
+        self.eliminated.get(index).copied().unwrap_or(false)
+    }
+
+    pub fn eliminated_count(&self) -> usize {
+        self.eliminated_count
+    }
+
+    fn eliminate(&mut self, index: usize) {
+        if self.eliminated.len() <= index {
+            self.eliminated.resize(index + 1, false);
+        }
+        if !self.eliminated[index] {
+            self.eliminated[index] = true;
+            self.eliminated_count += 1;
+        }
+    }
+
+    fn eliminated_indices(&self) -> impl Iterator<Item = usize> + '_ {
+        self.eliminated
+            .iter()
+            .enumerate()
+            .filter(|(_, &eliminated)| eliminated)
+            .map(|(index, _)| index)
+    }
+
+    /// Retires every item that is confidently outside the top `k`.
+    /// `intervals[i]` is `None` while item `i` has no usable interval yet.
+    pub fn update_eliminations(&mut self, intervals: &[Option<(f64, f64)>]) {
+        let active = self.active(intervals.len());
+        if active.len() <= self.k {
+            return;
+        }
+        let Some(threshold) =
+            kth_largest(active.iter().map(|&i| lower_bound(intervals[i])), self.k)
+        else {
+            return;
+        };
+        for &i in &active {
+            if let Some((_, high)) = intervals[i] {
+                if high < threshold {
+                    self.eliminate(i);
+                }
+            }
+        }
+    }
+
+    /// Active items that are neither retired nor confidently in the top `k`.
+    pub fn contenders(&self, intervals: &[Option<(f64, f64)>]) -> Vec<usize> {
+        let active = self.active(intervals.len());
+        if active.len() <= self.k {
+            return Vec::new();
+        }
+        let cutoff = kth_largest(
+            active.iter().map(|&i| upper_bound(intervals[i])),
+            self.k + 1,
+        )
+        .unwrap_or(f64::INFINITY);
+        active
+            .into_iter()
+            .filter(|&i| lower_bound(intervals[i]) <= cutoff)
+            .collect()
+    }
+
+    pub fn is_complete(&self, intervals: &[Option<(f64, f64)>]) -> bool {
+        self.contenders(intervals).is_empty()
+    }
+
+    pub fn set_k(&mut self, k: usize) {
+        if self.k != k.max(1) {
+            self.k = k.max(1);
+            self.eliminated.clear();
+            self.eliminated_count = 0;
+        }
+    }
+
+    pub fn remap(&self, old_ids: &[String], new_ids: &[String]) -> Self {
+        let positions: HashMap<&str, usize> = new_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.as_str(), index))
+            .collect();
+        let mut remapped = Self::new(self.k);
+        for index in self
+            .eliminated_indices()
+            .filter_map(|index| old_ids.get(index))
+            .filter_map(|id| positions.get(id.as_str()).copied())
+        {
+            remapped.eliminate(index);
+        }
+        remapped
+    }
+
+    fn active(&self, count: usize) -> Vec<usize> {
+        (0..count).filter(|&i| !self.is_eliminated(i)).collect()
+    }
+}
+
+impl MatchupStrategy for TopKState {
+    /// Pairs the least certain contender with the closest-rated active item,
+    /// preferring pairs that have met less often.
+    fn next_matchup(
+        &self,
+        context: &MatchupContext,
+        history: &[Matchup],
+        rng: &mut dyn RngCore,
+    ) -> Option<Matchup> {
+        let intervals = &context.intervals;
+        let last = history.last();
+        let mut contenders = self.contenders(intervals);
+        let mut active = self.active(intervals.len());
+        contenders.retain(|&i| context.is_allowed(i));
+        active.retain(|&i| context.is_allowed(i));
+        if contenders.is_empty() || active.len() < 2 {
+            return None;
+        }
+
+        let width = |i: usize| match intervals[i] {
+            Some((low, high)) => high - low,
+            None => f64::INFINITY,
+        };
+        let widest = contenders
+            .iter()
+            .map(|&i| width(i))
+            .fold(f64::NEG_INFINITY, f64::max);
+        let uncertain: Vec<usize> = contenders
+            .into_iter()
+            .filter(|&i| width(i) >= widest)
+            .collect();
+        let left_index = uncertain[rng.gen_range(0..uncertain.len())];
+
+        let rating = |i: usize| context.ratings.get(i).copied().unwrap_or(0.0);
+        let mut best: Vec<usize> = Vec::new();
+        let mut best_score = f64::INFINITY;
+        for &j in &active {
+            if j == left_index {
+                continue;
+            }
+            let gap = (rating(left_index) - rating(j)).abs();
+            let matches = pair_matches(context.win_matrix, context.tie_matrix, left_index, j);
+            let mut score = (1.0 + gap) * (1.0 + matches as f64);
+            if last.is_some_and(|previous| previous.is_pair(left_index, j)) {
+                score /= RECENT_PAIR_PENALTY;
+            }
+            if score < best_score {
+                best_score = score;
+                best.clear();
+                best.push(j);
+            } else if score == best_score {
+                best.push(j);
+            }
+        }
+        let right_index = *best.get(rng.gen_range(0..best.len().max(1)))?;
+
+        Some(Matchup {
+            left_index,
+            right_index,
+        })
+    }
+}
+
+fn lower_bound(interval: Option<(f64, f64)>) -> f64 {
+    interval.map(|(low, _)| low).unwrap_or(f64::NEG_INFINITY)
+}
+
+fn upper_bound(interval: Option<(f64, f64)>) -> f64 {
+    interval.map(|(_, high)| high).unwrap_or(f64::INFINITY)
+}
+
+fn kth_largest(values: impl Iterator<Item = f64>, k: usize) -> Option<f64> {
+    let mut values: Vec<f64> = values.collect();
+    if k == 0 || values.len() < k {
+        return None;
+    }
+    values.sort_by(|a, b| b.partial_cmp(a).unwrap_or(std::cmp::Ordering::Equal));
+    Some(values[k - 1])
+}
+
+fn ceil_log2(value: usize) -> usize {
+    if value <= 1 {
+        0
+    } else {
+        (usize::BITS - (value - 1).leading_zeros()) as usize
+    }
+}
+
 const TOP_BIAS_POWER: f64 = 0.15;
 const PROXIMITY_ALPHA: f64 = 4.0;
 const RECENT_PAIR_PENALTY: f64 = 0.35;
 const MIN_WEIGHT: f64 = 1e-9;
+const GAIN_TIE_EPSILON: f64 = 1e-12;
+const PRIOR_LOG_STRENGTH_DEVIATION: f64 = 1.5;
 
-pub fn random_matchup(
+fn adaptive_matchup(
     abilities: &[f64],
     win_matrix: &[Vec<u32>],
+    tie_matrix: &[Vec<u32>],
     match_totals: &[u32],
+    allowed: Option<&[bool]>,
     last: Option<&Matchup>,
+    rng: &mut dyn RngCore,
 ) -> Option<Matchup> {
     let count = abilities.len().min(win_matrix.len());
-    if count < 2 {
+    let pool = allowed_indices(count, allowed);
+    if pool.len() < 2 {
         return None;
     }
 
-    let mut rng = rand::thread_rng();
-
     // Bias first selection toward higher-rated items and those with fewer total matches.
     let total_ability: f64 = abilities.iter().copied().sum::<f64>().max(MIN_WEIGHT);
-    let mut first_weights = Vec::with_capacity(count);
-    let total_items = count as f64;
-    let unseen_total = match_totals.iter().take(count).filter(|&&m| m == 0).count();
-    for i in 0..count {
-        let ability_bias = (abilities[i].max(MIN_WEIGHT) / total_ability).powf(TOP_BIAS_POWER);
+    let mut first_weights = Vec::with_capacity(pool.len());
+    let total_items = pool.len() as f64;
+    let unseen_total = pool
+        .iter()
+        .filter(|&&i| match_totals.get(i).is_some_and(|&m| m == 0))
+        .count();
+    for &i in &pool {
+        let ability = abilities[i];
+        let ability_bias = (ability.max(MIN_WEIGHT) / total_ability).powf(TOP_BIAS_POWER);
         let total_matches = match_totals.get(i).copied().unwrap_or_else(|| {
-            win_matrix[i].iter().copied().sum::<u32>()
-                + win_matrix
-                    .iter()
-                    .enumerate()
-                    .filter_map(|(row_idx, row)| if row_idx == i { None } else { row.get(i) })
-                    .copied()
-                    .sum::<u32>()
+            (0..count)
+                .filter(|&j| j != i)
+                .map(|j| pair_matches(win_matrix, tie_matrix, i, j))
+                .sum::<u32>()
         });
         let total_matches_f = total_matches as f64;
         let total_opponents = (count.saturating_sub(1)) as f64;
@@ -64,21 +706,16 @@ pub fn random_matchup(
         first_weights.push((ability_bias * uncertainty * seen_bias).max(MIN_WEIGHT));
     }
 
-    let left_index = sample_index(&first_weights, &mut rng)?;
+    let left_index = pool[sample_index(&first_weights, rng)?];
 
     // Determine which opponents are still fresh (no games recorded against `left_index`).
     let mut fresh_candidates = Vec::new();
     let mut fallback_candidates = Vec::new();
-    for j in 0..count {
+    for &j in &pool {
         if j == left_index {
             continue;
         }
-        let matches = win_matrix[left_index].get(j).copied().unwrap_or(0)
-            + win_matrix
-                .get(j)
-                .and_then(|row| row.get(left_index))
-                .copied()
-                .unwrap_or(0);
+        let matches = pair_matches(win_matrix, tie_matrix, left_index, j);
         if matches == 0 {
             fresh_candidates.push(j);
         }
@@ -102,27 +739,18 @@ pub fn random_matchup(
     for &j in &candidate_source {
         let rating_gap = (abilities[left_index] - abilities[j]).abs();
         let proximity_bias = (-PROXIMITY_ALPHA * rating_gap).exp();
-        let matches = win_matrix[left_index].get(j).copied().unwrap_or(0)
-            + win_matrix
-                .get(j)
-                .and_then(|row| row.get(left_index))
-                .copied()
-                .unwrap_or(0);
+        let matches = pair_matches(win_matrix, tie_matrix, left_index, j);
         let freshness_bias = 1.0 / (1.0 + matches as f64);
         let ability_bias = abilities[j].max(MIN_WEIGHT);
 
         let mut weight = ability_bias * proximity_bias * freshness_bias;
-        if let Some(previous) = last {
-            if (previous.left_index == left_index && previous.right_index == j)
-                || (previous.left_index == j && previous.right_index == left_index)
-            {
-                weight *= RECENT_PAIR_PENALTY;
-            }
+        if last.is_some_and(|previous| previous.is_pair(left_index, j)) {
+            weight *= RECENT_PAIR_PENALTY;
         }
         second_weights.push(weight.max(MIN_WEIGHT));
     }
 
-    let right_index = if let Some(idx) = sample_index(&second_weights, &mut rng) {
+    let right_index = if let Some(idx) = sample_index(&second_weights, rng) {
         candidate_source[idx]
     } else {
         let mut remaining = candidate_source;
@@ -135,7 +763,25 @@ pub fn random_matchup(
     })
 }
 
-fn sample_index(weights: &[f64], rng: &mut impl Rng) -> Option<usize> {
+/// Indices below `count` that may be paired.
+fn allowed_indices(count: usize, allowed: Option<&[bool]>) -> Vec<usize> {
+    (0..count)
+        .filter(|&i| allowed.is_none_or(|allowed| allowed.get(i).copied().unwrap_or(false)))
+        .collect()
+}
+
+fn pair_matches(win_matrix: &[Vec<u32>], tie_matrix: &[Vec<u32>], i: usize, j: usize) -> u32 {
+    let cell = |matrix: &[Vec<u32>], row: usize, col: usize| {
+        matrix
+            .get(row)
+            .and_then(|values| values.get(col))
+            .copied()
+            .unwrap_or(0)
+    };
+    cell(win_matrix, i, j) + cell(win_matrix, j, i) + cell(tie_matrix, i, j)
+}
+
+fn sample_index(weights: &[f64], rng: &mut dyn RngCore) -> Option<usize> {
     if weights.is_empty() {
         return None;
     }
@@ -156,3 +802,200 @@ fn sample_index(weights: &[f64], rng: &mut impl Rng) -> Option<usize> {
         .ok()
         .map(|dist| dist.sample(rng))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::BradleyTerry;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    fn run_sort(strengths: &[u32]) -> (InsertionSort, usize) {
+        let mut sort = InsertionSort::new(strengths.len());
+        let mut comparisons = 0;
+        while let Some(matchup) = sort.next_matchup() {
+            let winner = if strengths[matchup.left_index] >= strengths[matchup.right_index] {
+                matchup.left_index
+            } else {
+                matchup.right_index
+            };
+            sort.record(matchup.left_index, matchup.right_index, Some(winner));
+            comparisons += 1;
+        }
+        (sort, comparisons)
+    }
+
+    #[test]
+    fn insertion_sort_orders_items() {
+        let strengths = [5, 1, 9, 3, 7, 2, 8, 6, 4, 0];
+        let (sort, comparisons) = run_sort(&strengths);
+
+        assert!(sort.is_complete());
+        assert_eq!(sort.order(), &[2, 6, 4, 7, 0, 8, 3, 5, 1, 9]);
+        // Binary insertion of 10 items needs at most 25 comparisons.
+        assert!(comparisons <= 25);
+    }
+
+    #[test]
+    fn comparisons_remaining_bounds_the_work() {
+        let sort = InsertionSort::new(10);
+        let (_, used) = run_sort(&[5, 1, 9, 3, 7, 2, 8, 6, 4, 0]);
+        assert!(sort.comparisons_remaining() >= used);
+        assert_eq!(InsertionSort::new(1).comparisons_remaining(), 0);
+    }
+
+    #[test]
+    fn top_k_eliminates_confident_losers() {
+        let intervals = vec![
+            Some((1200.0, 1300.0)),
+            Some((1150.0, 1250.0)),
+            Some((900.0, 1000.0)),
+            Some((1100.0, 1180.0)),
+            None,
+        ];
+        let mut top_k = TopKState::new(2);
+        top_k.update_eliminations(&intervals);
+
+        assert!(top_k.is_eliminated(2));
+        assert!(!top_k.is_eliminated(3));
+        assert!(!top_k.is_eliminated(4));
+        assert_eq!(top_k.eliminated_count(), 1);
+        assert!(!top_k.is_complete(&intervals));
+    }
+
+    #[test]
+    fn top_k_saves_eliminations_as_indices() {
+        let mut top_k = TopKState::new(2);
+        top_k.update_eliminations(&[
+            Some((1200.0, 1300.0)),
+            Some((1150.0, 1250.0)),
+            Some((900.0, 1000.0)),
+        ]);
+
+        let saved = serde_json::to_value(&top_k).unwrap();
+        assert_eq!(saved, serde_json::json!({ "k": 2, "eliminated": [2] }));
+        let restored: TopKState = serde_json::from_value(saved).unwrap();
+        assert_eq!(restored, top_k);
+    }
+
+    #[test]
+    fn top_k_completes_when_gap_is_clear() {
+        let intervals = vec![
+            Some((1200.0, 1300.0)),
+            Some((1150.0, 1250.0)),
+            Some((900.0, 1000.0)),
+        ];
+        let mut top_k = TopKState::new(2);
+        top_k.update_eliminations(&intervals);
+
+        assert!(top_k.is_complete(&intervals));
+        let wins = vec![vec![0; 3]; 3];
+        let context = MatchupContext {
+            abilities: &[0.5, 0.4, 0.1],
+            win_matrix: &wins,
+            tie_matrix: &[],
+            match_totals: &[],
+            ratings: vec![1250.0, 1200.0, 950.0],
+            intervals,
+            log_strengths: Vec::new(),
+            deviations: Vec::new(),
+            allowed: None,
+        };
+        let mut rng = StdRng::seed_from_u64(1);
+        assert!(top_k.next_matchup(&context, &[], &mut rng).is_none());
+    }
+
+    #[test]
+    fn information_gain_prefers_close_uncertain_pairs() {
+        // 0 and 1 are close but well known, 0 and 2 are far apart,
+        // 1 and 3 are close and both still uncertain.
+        let means = [1.0, 0.9, -3.0, 0.8];
+        let deviations = [0.05, 0.8, 0.8, 0.8];
+
+        let mut rng = StdRng::seed_from_u64(3);
+        let matchup = information_gain_matchup(&means, &deviations, None, None, &mut rng).unwrap();
+        let mut pair = [matchup.left_index, matchup.right_index];
+        pair.sort_unstable();
+        assert_eq!(pair, [1, 3]);
+    }
+
+    #[test]
+    fn information_gain_is_zero_without_uncertainty() {
+        assert_eq!(expected_information_gain(0.5, 0.0), 0.0);
+        assert!(expected_information_gain(0.0, 1.0) > expected_information_gain(4.0, 1.0));
+    }
+
+    #[test]
+    fn seeded_strategies_are_reproducible() {
+        let wins = vec![
+            vec![0, 2, 1, 0, 3],
+            vec![1, 0, 0, 2, 1],
+            vec![0, 1, 0, 1, 0],
+            vec![2, 0, 1, 0, 1],
+            vec![0, 1, 2, 0, 0],
+        ];
+        let ties = vec![vec![0; 5]; 5];
+        let totals = [7, 7, 6, 6, 8];
+        let mut engine = BradleyTerry::new(5);
+        engine.fit_default(&wins, &ties);
+        let context = MatchupContext::new(&engine, &wins, &ties, &totals);
+        let history = [Matchup {
+            left_index: 0,
+            right_index: 1,
+        }];
+
+        let strategies: [&dyn MatchupStrategy; 2] = [&AdaptiveStrategy, &InformationGainStrategy];
+        for strategy in strategies {
+            let run = |seed| {
+                let mut rng = StdRng::seed_from_u64(seed);
+                (0..20)
+                    .map(|_| strategy.next_matchup(&context, &history, &mut rng))
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(run(42), run(42));
+            assert!(run(42).iter().all(|matchup| matchup
+                .as_ref()
+                .is_some_and(|m| m.left_index != m.right_index && m.right_index < 5)));
+        }
+    }
+
+    #[test]
+    fn filtered_strategies_stay_inside_the_filter() {
+        let wins = vec![vec![0; 6]; 6];
+        let ties = vec![vec![0; 6]; 6];
+        let totals = [0; 6];
+        let engine = BradleyTerry::new(6);
+        let allowed = vec![false, true, false, true, true, false];
+        let context =
+            MatchupContext::new(&engine, &wins, &ties, &totals).with_allowed(Some(allowed.clone()));
+
+        let sort = InsertionSort::new(6);
+        let top_k = TopKState::new(2);
+        let strategies: [&dyn MatchupStrategy; 4] =
+            [&AdaptiveStrategy, &InformationGainStrategy, &sort, &top_k];
+        let mut rng = StdRng::seed_from_u64(7);
+        for strategy in strategies {
+            for _ in 0..20 {
+                let matchup = strategy.next_matchup(&context, &[], &mut rng).unwrap();
+                assert!(allowed[matchup.left_index] && allowed[matchup.right_index]);
+                assert_ne!(matchup.left_index, matchup.right_index);
+            }
+        }
+
+        let single = context.with_allowed(Some(vec![true, false, false, false, false, false]));
+        assert!(AdaptiveStrategy
+            .next_matchup(&single, &[], &mut rng)
+            .is_none());
+    }
+
+    #[test]
+    fn remap_keeps_relative_order() {
+        let old_ids: Vec<String> = ["a", "b", "c"].iter().map(|s| s.to_string()).collect();
+        let new_ids: Vec<String> = ["c", "d", "a"].iter().map(|s| s.to_string()).collect();
+        let (sort, _) = run_sort(&[1, 2, 3]);
+
+        let remapped = sort.remap(&old_ids, &new_ids);
+        assert_eq!(remapped.order(), &[0, 2]);
+        assert!(!remapped.is_complete());
+    }
+}
@@ -1,16 +1,161 @@
+use crate::glicko::{Glicko2, GlickoRating};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
 const MIN_ABILITY: f64 = 1e-6;
 const DISPLAY_BASE: f64 = 1000.0;
 const DISPLAY_SCALE: f64 = 200.0;
+const MAX_TIE_STRENGTH: f64 = 10.0;
+
+pub const INTERVAL_Z: f64 = 1.96;
+pub const DEFAULT_TOLERANCE: f64 = 1e-4;
+pub const DEFAULT_MAX_ITERATIONS: usize = 250;
+/// MM updates run after each comparison, starting from the current abilities.
+/// The full fit waits until the list is loaded or the rankings are shown.
+const RECORD_ITERATIONS: usize = 6;
+
+/// Outcome of a convergence-based [`BradleyTerry::fit`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FitReport {
+    pub iterations: usize,
+    pub max_delta: f64,
+    pub converged: bool,
+    /// Comparisons have been folded in with a few warm-started iterations
+    /// since this fit ran.
+    pub stale: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RatingEngineKind {
+    #[default]
+    BradleyTerry,
+    Glicko2,
+}
+
+impl RatingEngineKind {
+    pub const ALL: [RatingEngineKind; 2] =
+        [RatingEngineKind::BradleyTerry, RatingEngineKind::Glicko2];
+
+    pub fn label(self) -> &'static str {
+        match self {
+            RatingEngineKind::BradleyTerry => "Bradley-Terry",
+            RatingEngineKind::Glicko2 => "Glicko-2",
+        }
+    }
+}
 
+/// A rating model that turns recorded comparisons into per-item ratings.
+///
+/// `wins` and `ties` are always the full, already-updated matrices, so engines
+/// that refit from scratch and engines that update incrementally can share the
+/// same call sites.
+pub trait RatingEngine: fmt::Debug {
+    fn kind(&self) -> RatingEngineKind;
+
+    fn ensure_len(&mut self, len: usize);
+
+    /// Relative strengths on a positive scale, normalized to sum to one.
+    fn abilities(&self) -> &[f64];
+
+    /// Recomputes every rating from the aggregated matrices.
+    fn rebuild(&mut self, wins: &[Vec<u32>], ties: &[Vec<u32>]);
+
+    /// Folds in one new comparison; `score` is 1.0 when `i` won, 0.5 for a tie.
+    fn record(&mut self, i: usize, j: usize, score: f64, wins: &[Vec<u32>], ties: &[Vec<u32>]);
+
+    /// Finishes a fit that [`RatingEngine::record`] only approximates, for
+    /// engines that have one.
+    fn converge(&mut self, _wins: &[Vec<u32>], _ties: &[Vec<u32>]) {}
+
+    fn display_rating(&self, index: usize) -> f64;
+
+    /// Display-rating points per unit of log-odds, used to convert ratings
+    /// and margins back into the logistic scale.
+    fn display_scale(&self) -> f64;
+
+    /// Half-width of the 95% interval around [`RatingEngine::display_rating`].
+    fn rating_margin(&self, index: usize) -> Option<f64>;
+
+    fn rating_interval(&self, index: usize) -> Option<(f64, f64)> {
+        let margin = self.rating_margin(index)?;
+        let rating = self.display_rating(index);
+        Some(((rating - margin).max(0.0), rating + margin))
+    }
+
+    fn rating_intervals(&self) -> Vec<Option<(f64, f64)>> {
+        (0..self.abilities().len())
+            .map(|index| self.rating_interval(index))
+            .collect()
+    }
+
+    fn fit_report(&self) -> Option<&FitReport> {
+        None
+    }
+
+    /// Per-item Glicko-2 state to persist, for engines that carry it.
+    fn glicko_ratings(&self) -> Option<&[GlickoRating]> {
+        None
+    }
+
+    fn clone_box(&self) -> Box<dyn RatingEngine>;
+}
+
+impl Clone for Box<dyn RatingEngine> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+/// Restores the engine of `kind` from persisted abilities or Glicko-2 state.
+/// Glicko-2 ratings are rebuilt from the matrices when none were saved yet.
+pub fn restore_engine(
+    kind: RatingEngineKind,
+    abilities: &[f64],
+    glicko: &[GlickoRating],
+    wins: &[Vec<u32>],
+    ties: &[Vec<u32>],
+) -> Box<dyn RatingEngine> {
+    let count = wins.len();
+    let mut engine: Box<dyn RatingEngine> = match kind {
+        RatingEngineKind::BradleyTerry => {
+            Box::new(BradleyTerry::from_abilities(abilities.to_vec()))
+        }
+        RatingEngineKind::Glicko2 if glicko.len() == count => {
+            Box::new(Glicko2::from_ratings(glicko.to_vec()))
+        }
+        RatingEngineKind::Glicko2 => {
+            let mut system = Glicko2::new(count);
+            system.rebuild(wins, ties);
+            Box::new(system)
+        }
+    };
+    engine.ensure_len(count);
+    if kind == RatingEngineKind::BradleyTerry {
+        engine.rebuild(wins, ties);
+    }
+    engine
+}
+
+/// Bradley-Terry model extended with Davidson's tie parameter.
+///
+/// With no recorded ties `tie_strength` stays at zero and the model reduces to
+/// the plain Bradley-Terry fit.
 #[derive(Debug, Clone)]
 pub struct BradleyTerry {
     abilities: Vec<f64>,
+    tie_strength: f64,
+    last_fit: Option<FitReport>,
+    standard_errors: Vec<Option<f64>>,
 }
 
 impl BradleyTerry {
     pub fn new(count: usize) -> Self {
         Self {
             abilities: vec![1.0; count],
+            tie_strength: 0.0,
+            last_fit: None,
+            standard_errors: Vec::new(),
         }
     }
 
@@ -23,6 +168,9 @@ impl BradleyTerry {
                     .into_iter()
                     .map(|value| value.max(MIN_ABILITY))
                     .collect(),
+                tie_strength: 0.0,
+                last_fit: None,
+                standard_errors: Vec::new(),
             }
         }
     }
@@ -36,6 +184,9 @@ impl BradleyTerry {
     }
 
     pub fn ensure_len(&mut self, len: usize) {
+        if self.abilities.len() != len {
+            self.standard_errors.clear();
+        }
         if self.abilities.len() < len {
             self.abilities
                 .resize(len, if len > 0 { 1.0 / len as f64 } else { 1.0 });
@@ -47,53 +198,226 @@ impl BradleyTerry {
         }
     }
 
+    pub fn tie_strength(&self) -> f64 {
+        self.tie_strength
+    }
+
+    /// Expected score of `i` against `j`, counting a tie as half a win.
     pub fn expected_score(&self, i: usize, j: usize) -> f64 {
         if self.abilities.is_empty() {
             return 0.5;
         }
         let ai = self.abilities[i].max(MIN_ABILITY);
         let aj = self.abilities[j].max(MIN_ABILITY);
-        ai / (ai + aj)
+        let draw = self.tie_strength * (ai * aj).sqrt();
+        (ai + 0.5 * draw) / (ai + aj + draw)
+    }
+
+    pub fn tie_probability(&self, i: usize, j: usize) -> f64 {
+        if self.abilities.is_empty() {
+            return 0.0;
+        }
+        let ai = self.abilities[i].max(MIN_ABILITY);
+        let aj = self.abilities[j].max(MIN_ABILITY);
+        let draw = self.tie_strength * (ai * aj).sqrt();
+        draw / (ai + aj + draw)
     }
 
     pub fn run_iterations(&mut self, wins: &[Vec<u32>], iterations: usize) {
+        self.run_iterations_with_ties(wins, &[], iterations);
+    }
+
+    /// Runs Hunter's MM updates for the Davidson model. `ties` is a symmetric
+    /// matrix of draw counts; pass an empty slice when there are none.
+    pub fn run_iterations_with_ties(
+        &mut self,
+        wins: &[Vec<u32>],
+        ties: &[Vec<u32>],
+        iterations: usize,
+    ) {
         let n = wins.len();
         if n == 0 || iterations == 0 {
             return;
         }
         self.ensure_len(n);
 
-        let mut abilities = self.abilities.clone();
-
+        let total_ties = count_ties(ties, n);
+        let games = pair_games(wins, ties);
         for _ in 0..iterations {
-            let mut updated = abilities.clone();
-            for i in 0..n {
-                let pseudo = if n > 0 {
-                    (1.0 / n as f64).max(MIN_ABILITY)
-                } else {
-                    0.0
-                };
-                let wins_i: f64 = wins[i].iter().map(|&w| w as f64 + pseudo).sum();
+            self.step(wins, ties, &games, total_ties);
+        }
+    }
+
+    /// Iterates until the largest relative change in any ability (or in the
+    /// tie strength) drops below `tolerance`, or `max_iterations` is reached.
+    pub fn fit(
+        &mut self,
+        wins: &[Vec<u32>],
+        ties: &[Vec<u32>],
+        tolerance: f64,
+        max_iterations: usize,
+    ) -> FitReport {
+        let n = wins.len();
+        let mut report = FitReport {
+            iterations: 0,
+            max_delta: 0.0,
+            converged: n == 0,
+            stale: false,
+        };
+        if n > 0 {
+            self.ensure_len(n);
+
+            let total_ties = count_ties(ties, n);
+            let games = pair_games(wins, ties);
+            while report.iterations < max_iterations {
+                report.max_delta = self.step(wins, ties, &games, total_ties);
+                report.iterations += 1;
+                if report.max_delta < tolerance {
+                    report.converged = true;
+                    break;
+                }
+            }
+        }
 
-                let mut denom = 0.0;
-                for j in 0..n {
-                    if i == j {
+        self.standard_errors = self.compute_standard_errors(wins, ties);
+        self.last_fit = Some(report);
+        report
+    }
+
+    pub fn fit_default(&mut self, wins: &[Vec<u32>], ties: &[Vec<u32>]) -> FitReport {
+        self.fit(wins, ties, DEFAULT_TOLERANCE, DEFAULT_MAX_ITERATIONS)
+    }
+
+    /// Report from the most recent call to [`BradleyTerry::fit`], if any.
+    pub fn last_fit(&self) -> Option<&FitReport> {
+        self.last_fit.as_ref()
+    }
+
+    /// Standard error of the log-ability of `index`, available after
+    /// [`BradleyTerry::fit`] once the item has at least one recorded match.
+    pub fn standard_error(&self, index: usize) -> Option<f64> {
+        self.standard_errors.get(index).copied().flatten()
+    }
+
+    /// 95% interval for [`BradleyTerry::display_rating`], as `(low, high)`.
+    pub fn rating_interval(&self, index: usize) -> Option<(f64, f64)> {
+        let margin = self.rating_margin(index)?;
+        let rating = self.display_rating(index);
+        Some(((rating - margin).max(0.0), rating + margin))
+    }
+
+    /// Half-width of the 95% interval on the display scale.
+    pub fn rating_margin(&self, index: usize) -> Option<f64> {
+        self.standard_error(index)
+            .map(|error| INTERVAL_Z * DISPLAY_SCALE * error)
+    }
+
+    /// Uses the diagonal of the observed Fisher information for the
+    /// log-abilities. Ignoring the off-diagonal terms keeps this to the pairs
+    /// that have met, at the cost of slightly understating the uncertainty of
+    /// sparse lists.
+    fn compute_standard_errors(&self, wins: &[Vec<u32>], ties: &[Vec<u32>]) -> Vec<Option<f64>> {
+        let n = self.abilities.len().min(wins.len());
+        let games = pair_games(wins, ties);
+
+        (0..n)
+            .map(|i| {
+                let mut information = 0.0;
+                for &(j, total) in &games[i] {
+                    if j >= n {
                         continue;
                     }
-                    let total = wins[i][j] as f64 + wins[j][i] as f64 + 2.0 * pseudo;
-                    denom += total / (abilities[i] + abilities[j] + MIN_ABILITY);
+                    let expected = self.expected_score(i, j);
+                    let tie = self.tie_probability(i, j);
+                    let win = expected - 0.5 * tie;
+                    let variance = win + 0.25 * tie - expected * expected;
+                    information += total * variance.max(0.0);
                 }
-
-                if denom > 0.0 {
-                    updated[i] = (wins_i / denom).max(MIN_ABILITY);
+                if information > 0.0 {
+                    Some(1.0 / information.sqrt())
+                } else {
+                    None
                 }
+            })
+            .collect()
+    }
+
+    /// Applies one MM update and returns the largest relative change. `games`
+    /// comes from [`pair_games`] for the same matrices.
+    fn step(
+        &mut self,
+        wins: &[Vec<u32>],
+        ties: &[Vec<u32>],
+        games: &[Vec<(usize, f64)>],
+        total_ties: f64,
+    ) -> f64 {
+        let n = self.abilities.len();
+        let abilities = &self.abilities;
+        let tie_strength = if total_ties > 0.0 {
+            self.tie_strength
+        } else {
+            0.0
+        };
+        let tie_count = |i: usize, j: usize| -> f64 {
+            ties.get(i).and_then(|row| row.get(j)).copied().unwrap_or(0) as f64
+        };
+        let pseudo = (1.0 / n as f64).max(MIN_ABILITY);
+
+        let mut updated = abilities.clone();
+        for i in 0..n {
+            let ties_i: f64 = if total_ties > 0.0 {
+                (0..n).filter(|&j| j != i).map(|j| tie_count(i, j)).sum()
+            } else {
+                0.0
+            };
+            let wins_i: f64 =
+                wins[i].iter().map(|&w| w as f64 + pseudo).sum::<f64>() + 0.5 * ties_i;
+
+            // Without ties the Davidson terms vanish; skipping their square
+            // roots keeps long lists responsive.
+            let weight = |j: usize| {
+                let (draw, share) = if tie_strength > 0.0 {
+                    (
+                        tie_strength * (abilities[i] * abilities[j]).sqrt(),
+                        1.0 + 0.5 * tie_strength * (abilities[j] / abilities[i]).sqrt(),
+                    )
+                } else {
+                    (0.0, 1.0)
+                };
+                share / (abilities[i] + abilities[j] + draw + MIN_ABILITY)
+            };
+            let mut denom: f64 = (0..n)
+                .filter(|&j| j != i)
+                .map(|j| 2.0 * pseudo * weight(j))
+                .sum();
+            for &(j, count) in &games[i] {
+                denom += count * weight(j);
             }
 
-            normalize(&mut updated);
-            abilities = updated;
+            if denom > 0.0 {
+                updated[i] = (wins_i / denom).max(MIN_ABILITY);
+            }
         }
+        normalize(&mut updated);
 
-        self.abilities = abilities;
+        let mut max_delta = abilities
+            .iter()
+            .zip(&updated)
+            .map(|(old, new)| relative_change(*old, *new))
+            .fold(0.0, f64::max);
+
+        let updated_tie_strength = if total_ties > 0.0 {
+            estimate_tie_strength(&updated, wins, ties, tie_strength)
+        } else {
+            0.0
+        };
+        if total_ties > 0.0 {
+            max_delta = max_delta.max(relative_change(tie_strength, updated_tie_strength));
+        }
+
+        self.abilities = updated;
+        self.tie_strength = updated_tie_strength;
+        max_delta
     }
 
     pub fn log_score(&self, index: usize) -> f64 {
@@ -121,6 +445,119 @@ impl BradleyTerry {
     }
 }
 
+impl RatingEngine for BradleyTerry {
+    fn kind(&self) -> RatingEngineKind {
+        RatingEngineKind::BradleyTerry
+    }
+
+    fn ensure_len(&mut self, len: usize) {
+        BradleyTerry::ensure_len(self, len);
+    }
+
+    fn abilities(&self) -> &[f64] {
+        &self.abilities
+    }
+
+    fn rebuild(&mut self, wins: &[Vec<u32>], ties: &[Vec<u32>]) {
+        self.fit_default(wins, ties);
+    }
+
+    fn record(&mut self, _i: usize, _j: usize, _score: f64, wins: &[Vec<u32>], ties: &[Vec<u32>]) {
+        self.run_iterations_with_ties(wins, ties, RECORD_ITERATIONS);
+        self.standard_errors = self.compute_standard_errors(wins, ties);
+        if let Some(report) = self.last_fit.as_mut() {
+            report.stale = true;
+        }
+    }
+
+    fn converge(&mut self, wins: &[Vec<u32>], ties: &[Vec<u32>]) {
+        self.fit_default(wins, ties);
+    }
+
+    fn display_rating(&self, index: usize) -> f64 {
+        BradleyTerry::display_rating(self, index)
+    }
+
+    fn display_scale(&self) -> f64 {
+        DISPLAY_SCALE
+    }
+
+    fn rating_margin(&self, index: usize) -> Option<f64> {
+        BradleyTerry::rating_margin(self, index)
+    }
+
+    fn fit_report(&self) -> Option<&FitReport> {
+        self.last_fit()
+    }
+
+    fn clone_box(&self) -> Box<dyn RatingEngine> {
+        Box::new(self.clone())
+    }
+}
+
+/// Opponents each item has played, as `(opponent, games)` with ties
+/// included. Most pairs of a long list never meet, so the MM steps only visit
+/// these and handle the pseudo-counts that every pair gets separately.
+fn pair_games(wins: &[Vec<u32>], ties: &[Vec<u32>]) -> Vec<Vec<(usize, f64)>> {
+    let mut games = vec![Vec::new(); wins.len()];
+    for (i, row) in wins.iter().enumerate() {
+        for (j, &count) in row.iter().enumerate() {
+            if count > 0 && i != j && j < games.len() {
+                games[i].push((j, count as f64));
+                games[j].push((i, count as f64));
+            }
+        }
+    }
+    for (i, row) in ties.iter().enumerate().take(games.len()) {
+        for (j, &count) in row.iter().enumerate() {
+            if count > 0 && i != j && j < games.len() {
+                games[i].push((j, count as f64));
+            }
+        }
+    }
+    games
+}
+
+fn count_ties(ties: &[Vec<u32>], n: usize) -> f64 {
+    ties.iter()
+        .take(n)
+        .enumerate()
+        .flat_map(|(i, row)| row.iter().take(n).skip(i + 1))
+        .map(|&count| count as f64)
+        .sum()
+}
+
+fn relative_change(old: f64, new: f64) -> f64 {
+    (new - old).abs() / old.abs().max(MIN_ABILITY)
+}
+
+fn estimate_tie_strength(
+    abilities: &[f64],
+    wins: &[Vec<u32>],
+    ties: &[Vec<u32>],
+    current: f64,
+) -> f64 {
+    let n = abilities.len();
+    let mut total_ties = 0.0;
+    let mut denom = 0.0;
+    for i in 0..n {
+        for j in (i + 1)..n {
+            let tied = ties.get(i).and_then(|row| row.get(j)).copied().unwrap_or(0) as f64;
+            let total = wins[i][j] as f64 + wins[j][i] as f64 + tied;
+            if total <= 0.0 {
+                continue;
+            }
+            total_ties += tied;
+            let geometric = (abilities[i] * abilities[j]).sqrt();
+            denom += total * geometric / (abilities[i] + abilities[j] + current * geometric);
+        }
+    }
+    if denom <= 0.0 {
+        return current;
+    }
+    (total_ties / denom).clamp(0.0, MAX_TIE_STRENGTH)
+}
+
 fn normalize(values: &mut [f64]) {
     let sum: f64 = values.iter().map(|v| v.max(MIN_ABILITY)).sum();
     if sum <= f64::EPSILON {
@@ -146,7 +583,7 @@ mod tests {
     #[test]
     fn normalization_keeps_sum_one() {
         let mut system = BradleyTerry::new(3);
-        system.run_iterations(&vec![vec![0, 5, 0], vec![0, 0, 0], vec![0, 0, 0]], 5);
+        system.run_iterations(&[vec![0, 5, 0], vec![0, 0, 0], vec![0, 0, 0]], 5);
         let sum: f64 = system.abilities().iter().sum();
         assert!((sum - 1.0).abs() < 1e-6);
     }
@@ -161,4 +598,94 @@ mod tests {
         assert!(system.abilities()[0] > system.abilities()[1]);
         assert!(system.abilities()[0] > system.abilities()[2]);
     }
+
+    #[test]
+    fn fit_reports_convergence() {
+        let wins = vec![vec![0, 3, 2], vec![1, 0, 2], vec![0, 1, 0]];
+
+        let mut system = BradleyTerry::new(3);
+        let report = system.fit(&wins, &[], 1e-8, 1000);
+
+        assert!(report.converged);
+        assert!(report.iterations > 1);
+        assert!(report.max_delta < 1e-8);
+        assert_eq!(system.last_fit(), Some(&report));
+
+        let mut capped = BradleyTerry::new(3);
+        let report = capped.fit(&wins, &[], 1e-12, 2);
+        assert!(!report.converged);
+        assert_eq!(report.iterations, 2);
+    }
+
+    #[test]
+    fn recording_defers_the_full_fit() {
+        let wins = vec![vec![0, 3, 2], vec![1, 0, 2], vec![0, 1, 0]];
+        let ties = vec![vec![0; 3]; 3];
+
+        let mut engine: Box<dyn RatingEngine> = Box::new(BradleyTerry::new(3));
+        engine.rebuild(&wins, &ties);
+        assert!(engine.fit_report().is_some());
+
+        let mut more = wins.clone();
+        more[2][0] += 1;
+        engine.record(2, 0, 1.0, &more, &ties);
+        assert!(engine.fit_report().is_some_and(|report| report.stale));
+        assert!(engine.rating_interval(2).is_some());
+
+        engine.converge(&more, &ties);
+        let mut fitted = BradleyTerry::new(3);
+        fitted.fit_default(&more, &ties);
+        assert!(engine
+            .fit_report()
+            .is_some_and(|report| report.converged && !report.stale));
+        for (a, b) in engine.abilities().iter().zip(fitted.abilities()) {
+            assert!((a - b).abs() < 1e-3);
+        }
+    }
+
+    #[test]
+    fn intervals_shrink_with_more_matches() {
+        let wins = vec![vec![0, 12, 1], vec![10, 0, 0], vec![0, 1, 0]];
+
+        let mut system = BradleyTerry::new(3);
+        assert_eq!(system.rating_interval(0), None);
+        system.fit_default(&wins, &[]);
+
+        let (low, high) = system.rating_interval(0).unwrap();
+        let rating = system.display_rating(0);
+        assert!(low < rating && rating < high);
+        assert!(system.rating_margin(0).unwrap() < system.rating_margin(2).unwrap());
+    }
+
+    #[test]
+    fn ties_pull_abilities_together() {
+        let wins = vec![vec![0, 3, 3], vec![0, 0, 3], vec![0, 0, 0]];
+        let ties = vec![vec![0, 2, 0], vec![2, 0, 0], vec![0, 0, 0]];
+
+        let mut without_ties = BradleyTerry::new(3);
+        without_ties.run_iterations(&wins, 20);
+        let mut with_ties = BradleyTerry::new(3);
+        with_ties.run_iterations_with_ties(&wins, &ties, 20);
+
+        let gap = |system: &BradleyTerry| system.log_score(0) - system.log_score(1);
+        assert!(gap(&with_ties) > 0.0);
+        assert!(gap(&with_ties) < gap(&without_ties));
+        assert!(with_ties.tie_strength() > 0.0);
+        assert!(with_ties.tie_probability(0, 1) > 0.0);
+    }
+
+    #[test]
+    fn no_ties_matches_plain_model() {
+        let wins = vec![vec![0, 3, 1], vec![1, 0, 2], vec![0, 1, 0]];
+
+        let mut plain = BradleyTerry::new(3);
+        plain.run_iterations(&wins, 10);
+        let mut tie_aware = BradleyTerry::new(3);
+        tie_aware.run_iterations_with_ties(&wins, &vec![vec![0; 3]; 3], 10);
+
+        for (a, b) in plain.abilities().iter().zip(tie_aware.abilities()) {
+            assert!((a - b).abs() < 1e-12);
+        }
+        assert_eq!(tie_aware.tie_strength(), 0.0);
+    }
 }
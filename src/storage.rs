@@ -1,22 +1,66 @@
+use crate::data::{CustomList, ListInfo};
+use crate::glicko::{Glicko2, GlickoRating};
+use crate::history::{tally, EventStamp, MatchEvent};
+use crate::matchflow::{
+    AdaptiveStrategy, InformationGainStrategy, InsertionSort, MatchupMode, MatchupStrategy,
+    TopKState, DEFAULT_TOP_K,
+};
+use crate::ranking::{restore_engine, RatingEngine, RatingEngineKind};
+#[cfg(feature = "web")]
 use gloo_storage::{LocalStorage, Storage};
+#[cfg(feature = "web")]
 use log::warn;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
 
+#[cfg(feature = "web")]
 const STORAGE_KEY: &str = "ranking_lists_state";
+/// Saved state that could not be read is moved to `<prefix>_<timestamp>`.
+#[cfg(feature = "web")]
+const BACKUP_KEY_PREFIX: &str = "ranking_lists_state_backup";
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Layout version written by [`encode_state`]. Raising it needs a matching
+/// entry in `MIGRATIONS`.
+pub const STATE_VERSION: u32 = 2;
+
+/// `MIGRATIONS[n]` upgrades the saved JSON from version `n` to `n + 1`.
+/// Version 0 is the bare `StoredAppState` written before the envelope.
+const MIGRATIONS: [fn(Value) -> Result<Value, String>; STATE_VERSION as usize] =
+    [backfill_ties_and_totals, events_from_counts];
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(default)]
 pub struct StoredAppState {
     pub selected_list: Option<String>,
     pub lists: HashMap<String, StoredListState>,
+    /// Lists created in the app, in the order they were made.
+    pub custom_lists: Vec<CustomList>,
+    /// Lists opened from a URL, with the text last fetched.
+    pub cached_lists: Vec<CachedList>,
+    /// Applies every comparison to all other lists that have both items.
+    pub shared_evidence: bool,
+    /// `exported` stamps of the backups made from or merged into this state,
+    /// so that merging the same backup again changes nothing.
+    pub backups: Vec<u64>,
 }
 
-impl Default for StoredAppState {
-    fn default() -> Self {
-        Self {
-            selected_list: None,
-            lists: HashMap::new(),
+/// Copy of a list loaded from a URL, so it still opens while the remote file
+/// is unavailable.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CachedList {
+    pub id: String,
+    pub title: String,
+    pub text: String,
+}
+
+impl CachedList {
+    pub fn info(&self) -> ListInfo {
+        ListInfo {
+            id: self.id.clone(),
+            label: self.title.clone(),
+            ..ListInfo::default()
         }
     }
 }
@@ -24,10 +68,121 @@ impl Default for StoredAppState {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StoredListState {
     pub item_ids: Vec<String>,
+    /// Derived from `events` by [`Self::replay`], like the tie matrix and the
+    /// totals; none of the three is saved.
+    #[serde(skip)]
     pub win_matrix: Vec<Vec<u32>>,
     pub abilities: Vec<f64>,
     #[serde(default)]
+    pub engine: RatingEngineKind,
+    /// Incremental Glicko-2 state; empty until the list switches to Glicko-2.
+    #[serde(default)]
+    pub glicko: Vec<GlickoRating>,
+    #[serde(default)]
+    pub mode: MatchupMode,
+    /// Progress of the exact-order sort; only kept while that mode is in use.
+    #[serde(default)]
+    pub sort: Option<InsertionSort>,
+    /// Top-K elimination progress; only kept while that mode is in use.
+    #[serde(default)]
+    pub top_k: Option<TopKState>,
+    #[serde(skip)]
     pub match_totals: Vec<u32>,
+    /// Symmetric counts of "can't decide" outcomes between two items.
+    #[serde(skip)]
+    pub tie_matrix: Vec<Vec<u32>>,
+    /// Every comparison counted in this list, oldest first, including those
+    /// copied in from other lists.
+    #[serde(default)]
+    pub events: Vec<MatchEvent>,
+    /// Own decisions taken back by [`Self::undo`], most recent last. Making a
+    /// new decision clears it.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub redo: Vec<MatchEvent>,
+    /// Outcomes already copied in from the lists a composite list is built
+    /// from, by source list id.
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub inherited: HashMap<String, InheritedResults>,
+    /// Menu query limiting which items are paired and listed; see
+    /// [`crate::filter::ItemFilter`]. Outcomes still count for every item.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub filter: String,
+}
+
+/// What [`StoredListState::undo`] and the other history edits changed, so the
+/// caller can pass it on to the lists that shared the outcome.
+pub struct HistoryEdit {
+    pub removed: Vec<MatchEvent>,
+    pub added: Vec<MatchEvent>,
+    /// Engine refit to the edited history.
+    pub engine: Box<dyn RatingEngine>,
+}
+
+/// Sparse copy of another list's outcomes, keyed by item id so it survives
+/// either list changing.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InheritedResults {
+    /// `(winner, loser, count)`.
+    #[serde(default)]
+    pub wins: Vec<(String, String, u32)>,
+    /// `(first, second, count)`, with the ids in sorted order.
+    #[serde(default)]
+    pub ties: Vec<(String, String, u32)>,
+}
+
+impl InheritedResults {
+    /// Outcomes of `source` between items that `positions` knows.
+    fn collect(source: &StoredListState, positions: &HashMap<&str, usize>) -> Self {
+        let ids = &source.item_ids;
+        let shared = |index: usize| {
+            ids.get(index)
+                .filter(|id| positions.contains_key(id.as_str()))
+        };
+        let mut results = Self::default();
+        for (i, row) in source.win_matrix.iter().enumerate() {
+            for (j, &count) in row.iter().enumerate() {
+                if let (true, Some(winner), Some(loser)) = (count > 0, shared(i), shared(j)) {
+                    results.wins.push((winner.clone(), loser.clone(), count));
+                }
+            }
+        }
+        for (i, row) in source.tie_matrix.iter().enumerate() {
+            for (j, &count) in row.iter().enumerate().skip(i + 1) {
+                if let (true, Some(a), Some(b)) = (count > 0, shared(i), shared(j)) {
+                    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+                    results.ties.push((first.clone(), second.clone(), count));
+                }
+            }
+        }
+        results
+    }
+
+    /// Counts one more (`delta` 1) or one fewer (`delta` -1) outcome.
+    fn adjust(&mut self, left: &str, right: &str, winner: Option<&str>, delta: i64) {
+        let (entries, first, second) = match winner {
+            Some(winner) => {
+                let loser = if winner == left { right } else { left };
+                (&mut self.wins, winner, loser)
+            }
+            None if left <= right => (&mut self.ties, left, right),
+            None => (&mut self.ties, right, left),
+        };
+        match entries
+            .iter()
+            .position(|(a, b, _)| a == first && b == second)
+        {
+            Some(position) => {
+                add_count(&mut entries[position].2, delta);
+                if entries[position].2 == 0 {
+                    entries.remove(position);
+                }
+            }
+            None if delta > 0 => {
+                entries.push((first.to_string(), second.to_string(), delta as u32));
+            }
+            None => {}
+        }
+    }
 }
 
 impl StoredListState {
@@ -37,7 +192,41 @@ impl StoredListState {
             item_ids: item_ids.to_vec(),
             win_matrix: vec![vec![0; count]; count],
             abilities: vec![1.0; count],
+            engine: RatingEngineKind::default(),
+            glicko: Vec::new(),
+            mode: MatchupMode::default(),
+            sort: None,
+            top_k: None,
             match_totals: vec![0; count],
+            tie_matrix: vec![vec![0; count]; count],
+            inherited: HashMap::new(),
+            filter: String::new(),
+            events: Vec::new(),
+            redo: Vec::new(),
+        }
+    }
+
+    /// Rebuilds the win, tie and total counts from `events`.
+    pub fn replay(&mut self) {
+        let counts = tally(&self.events, &self.item_ids);
+        self.win_matrix = counts.wins;
+        self.tie_matrix = counts.ties;
+        self.match_totals = counts.totals;
+    }
+
+    /// Removes the latest event with the same outcome and source as `event`.
+    /// The counts are left for the caller to update.
+    fn take_back(&mut self, event: &MatchEvent) -> bool {
+        let position = self
+            .events
+            .iter()
+            .rposition(|recorded| recorded.source == event.source && recorded.same_outcome(event));
+        match position {
+            Some(position) => {
+                self.events.remove(position);
+                true
+            }
+            None => false,
         }
     }
 
@@ -55,26 +244,678 @@ impl StoredListState {
         if self.match_totals.len() == self.item_ids.len() {
             self.match_totals.iter().copied().sum::<u32>() / 2
         } else {
-            self.win_matrix
+            let wins: u32 = self
+                .win_matrix
                 .iter()
                 .map(|row| row.iter().sum::<u32>())
-                .sum()
+                .sum();
+            let ties: u32 = self
+                .tie_matrix
+                .iter()
+                .map(|row| row.iter().sum::<u32>())
+                .sum();
+            wins + ties / 2
+        }
+    }
+
+    /// Builds the list's selected rating engine from the persisted state.
+    pub fn restore_engine(&self) -> Box<dyn RatingEngine> {
+        let replayed;
+        let glicko = if self.engine == RatingEngineKind::Glicko2
+            && self.glicko.len() != self.item_ids.len()
+        {
+            replayed = self.glicko_from_events();
+            &replayed
+        } else {
+            &self.glicko
+        };
+        restore_engine(
+            self.engine,
+            &self.abilities,
+            glicko,
+            &self.win_matrix,
+            &self.tie_matrix,
+        )
+    }
+
+    /// Glicko-2 ratings depend on the order of the games, so whenever the log
+    /// changes anywhere but at its end they are rated again from the first
+    /// event on. Lists on another engine keep no Glicko-2 state.
+    fn replay_glicko(&mut self) {
+        self.glicko = match self.engine {
+            RatingEngineKind::Glicko2 => self.glicko_from_events(),
+            RatingEngineKind::BradleyTerry => Vec::new(),
+        };
+    }
+
+    fn glicko_from_events(&self) -> Vec<GlickoRating> {
+        let positions: HashMap<&str, usize> = self
+            .item_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.as_str(), index))
+            .collect();
+        let mut system = Glicko2::new(self.item_ids.len());
+        system.record_games(self.events.iter().filter_map(|event| {
+            let i = *positions.get(event.winner.as_str())?;
+            let j = *positions.get(event.loser.as_str())?;
+            Some((i, j, if event.tie { 0.5 } else { 1.0 }))
+        }));
+        system.ratings().to_vec()
+    }
+
+    /// Copies the engine's ratings back so they survive a reload.
+    pub fn store_engine(&mut self, engine: &dyn RatingEngine) {
+        self.engine = engine.kind();
+        self.abilities = engine.abilities().to_vec();
+        self.glicko = engine
+            .glicko_ratings()
+            .map(|ratings| ratings.to_vec())
+            .unwrap_or_default();
+    }
+
+    /// Switches the matchup mode, creating whatever progress state the new
+    /// mode needs and dropping the state of the old one.
+    pub fn set_mode(&mut self, mode: MatchupMode) {
+        self.mode = mode;
+        self.ensure_mode_state();
+    }
+
+    pub fn ensure_mode_state(&mut self) {
+        if self.mode == MatchupMode::ExactOrder {
+            if self.sort.is_none() {
+                self.sort = Some(InsertionSort::new(self.item_ids.len()));
+            }
+        } else {
+            self.sort = None;
+        }
+
+        if self.mode == MatchupMode::TopK {
+            if self.top_k.is_none() {
+                let k = DEFAULT_TOP_K.min(self.item_ids.len().saturating_sub(1));
+                self.top_k = Some(TopKState::new(k));
+            }
+        } else {
+            self.top_k = None;
+        }
+    }
+
+    /// Logs one comparison and applies it to the matrices, the engine and the
+    /// mode state. `winner` is `None` for a tie. Returns `false` and changes
+    /// nothing when the indices do not fit this list.
+    pub fn record_result(
+        &mut self,
+        left_index: usize,
+        right_index: usize,
+        winner: Option<usize>,
+        engine: &mut dyn RatingEngine,
+        stamp: &EventStamp,
+    ) -> bool {
+        if !self.record_outcome(left_index, right_index, winner, stamp) {
+            return false;
+        }
+        let score = match winner {
+            Some(winner) if winner == left_index => 1.0,
+            Some(_) => 0.0,
+            None => 0.5,
+        };
+        engine.ensure_len(self.item_ids.len());
+        engine.record(
+            left_index,
+            right_index,
+            score,
+            &self.win_matrix,
+            &self.tie_matrix,
+        );
+        self.store_engine(engine);
+        self.refresh_eliminations(engine);
+        true
+    }
+
+    /// [`Self::record_result`] without the engine, for looking ahead at the
+    /// next matchup without paying for a refit.
+    pub fn record_outcome(
+        &mut self,
+        left_index: usize,
+        right_index: usize,
+        winner: Option<usize>,
+        stamp: &EventStamp,
+    ) -> bool {
+        let count = self.win_matrix.len();
+        if left_index >= count
+            || right_index >= count
+            || left_index == right_index
+            || self.item_ids.len() != count
+        {
+            return false;
+        }
+        let event = MatchEvent {
+            strategy: Some(self.mode),
+            ..MatchEvent::new(
+                &self.item_ids[left_index],
+                &self.item_ids[right_index],
+                winner.map(|index| self.item_ids[index].as_str()),
+            )
+            .stamped(stamp)
+        };
+
+        match winner {
+            Some(winner) if winner == left_index => {
+                self.win_matrix[left_index][right_index] =
+                    self.win_matrix[left_index][right_index].saturating_add(1);
+            }
+            Some(_) => {
+                self.win_matrix[right_index][left_index] =
+                    self.win_matrix[right_index][left_index].saturating_add(1);
+            }
+            None => {
+                if !self.has_tie_matrix() {
+                    return false;
+                }
+                self.tie_matrix[left_index][right_index] =
+                    self.tie_matrix[left_index][right_index].saturating_add(1);
+                self.tie_matrix[right_index][left_index] =
+                    self.tie_matrix[right_index][left_index].saturating_add(1);
+            }
+        }
+
+        for index in [left_index, right_index] {
+            if let Some(total) = self.match_totals.get_mut(index) {
+                *total = total.saturating_add(1);
+            }
+        }
+        self.events.push(event);
+        self.redo.clear();
+
+        if let Some(sort) = self.sort.as_mut() {
+            sort.record(left_index, right_index, winner);
+        }
+        true
+    }
+
+    /// True when there is a decision of this list's own to take back.
+    pub fn can_undo(&self) -> bool {
+        self.events.iter().any(|event| event.source.is_none())
+    }
+
+    /// Takes back the latest own decision and keeps it for [`Self::redo`].
+    pub fn undo(&mut self) -> Option<HistoryEdit> {
+        let index = self
+            .events
+            .iter()
+            .rposition(|event| event.source.is_none())?;
+        let event = self.events.remove(index);
+        self.redo.push(event.clone());
+        Some(self.rebuild(vec![event], Vec::new()))
+    }
+
+    /// Makes the decision most recently taken back by [`Self::undo`] again.
+    pub fn redo(&mut self) -> Option<HistoryEdit> {
+        let event = self.redo.pop()?;
+        self.events.push(event.clone());
+        Some(self.rebuild(Vec::new(), vec![event]))
+    }
+
+    /// Drops the own decision at `index` in `events`, keeping everything
+    /// after it.
+    pub fn revert(&mut self, index: usize) -> Option<HistoryEdit> {
+        if self.events.get(index)?.source.is_some() {
+            return None;
+        }
+        let event = self.events.remove(index);
+        Some(self.rebuild(vec![event], Vec::new()))
+    }
+
+    /// Gives the own decision at `index` in `events` to the other item. Ties
+    /// have no other side and are left alone.
+    pub fn flip(&mut self, index: usize) -> Option<HistoryEdit> {
+        let event = self.events.get_mut(index)?;
+        if event.source.is_some() || event.tie {
+            return None;
+        }
+        let previous = event.clone();
+        std::mem::swap(&mut event.winner, &mut event.loser);
+        let flipped = event.clone();
+        Some(self.rebuild(vec![previous], vec![flipped]))
+    }
+
+    fn rebuild(&mut self, removed: Vec<MatchEvent>, added: Vec<MatchEvent>) -> HistoryEdit {
+        HistoryEdit {
+            removed,
+            added,
+            engine: self.rebuild_from_events(),
+        }
+    }
+
+    /// Brings the counts, the engine and the mode progress in line with an
+    /// edited `events` log, and returns the refit engine.
+    pub(crate) fn rebuild_from_events(&mut self) -> Box<dyn RatingEngine> {
+        self.replay();
+        self.replay_glicko();
+        let engine = self.restore_engine();
+        self.store_engine(engine.as_ref());
+
+        if self.sort.is_some() {
+            self.sort = Some(self.replay_sort());
+        }
+        if let Some(top_k) = self.top_k.as_mut() {
+            *top_k = TopKState::new(top_k.k());
+        }
+        self.refresh_eliminations(engine.as_ref());
+        engine
+    }
+
+    /// Runs the trailing stretch of own exact-order decisions through a new
+    /// sort; switching modes drops the sort, so earlier ones never reached it.
+    fn replay_sort(&self) -> InsertionSort {
+        let mut sort = InsertionSort::new(self.item_ids.len());
+        let own: Vec<&MatchEvent> = self
+            .events
+            .iter()
+            .filter(|event| event.source.is_none())
+            .collect();
+        let start = own
+            .iter()
+            .rposition(|event| event.strategy != Some(MatchupMode::ExactOrder))
+            .map_or(0, |position| position + 1);
+        let position = |id: &str| self.item_ids.iter().position(|item_id| item_id == id);
+        for event in &own[start..] {
+            if let (Some(i), Some(j)) = (position(&event.winner), position(&event.loser)) {
+                sort.record(i, j, (!event.tie).then_some(i));
+            }
+        }
+        sort
+    }
+
+    /// Strategy for the current mode. Stateful modes hand out their saved
+    /// progress, so this is `None` until [`Self::ensure_mode_state`] has run.
+    pub fn strategy(&self) -> Option<&dyn MatchupStrategy> {
+        match self.mode {
+            MatchupMode::Adaptive => Some(&AdaptiveStrategy),
+            MatchupMode::ExactOrder => self.sort.as_ref().map(|sort| sort as &dyn MatchupStrategy),
+            MatchupMode::TopK => self
+                .top_k
+                .as_ref()
+                .map(|top_k| top_k as &dyn MatchupStrategy),
+            MatchupMode::InformationGain => Some(&InformationGainStrategy),
+        }
+    }
+
+    /// Adds the outcomes `source` gained since the last call, for pairs of
+    /// items this list also has; outcomes the source has since lost (undo,
+    /// reset) are taken back out. Returns whether anything changed, in which
+    /// case the engine has to be restored from the matrices again.
+    pub fn inherit_results(&mut self, source_id: &str, source: &StoredListState) -> bool {
+        if !self.matches_items(&self.item_ids) || !self.has_tie_matrix() {
+            return false;
+        }
+        let positions: HashMap<&str, usize> = self
+            .item_ids
+            .iter()
+            .enumerate()
+            .map(|(index, id)| (id.as_str(), index))
+            .collect();
+        let current = InheritedResults::collect(source, &positions);
+        let previous = self.inherited.get(source_id).cloned().unwrap_or_default();
+
+        let deltas: Vec<(bool, (String, String, i64))> =
+            count_deltas(&previous.wins, &current.wins)
+                .into_iter()
+                .map(|delta| (false, delta))
+                .chain(
+                    count_deltas(&previous.ties, &current.ties)
+                        .into_iter()
+                        .map(|delta| (true, delta)),
+                )
+                .filter(|(_, (a, b, _))| {
+                    positions.contains_key(a.as_str()) && positions.contains_key(b.as_str())
+                })
+                .collect();
+
+        let mut changed = false;
+        for (tie, (a, b, delta)) in deltas {
+            let event = MatchEvent {
+                source: Some(source_id.to_string()),
+                ..MatchEvent::new(&a, &b, (!tie).then_some(a.as_str()))
+            };
+            for _ in 0..delta.unsigned_abs() {
+                if delta > 0 {
+                    self.events.push(event.clone());
+                } else {
+                    self.take_back(&event);
+                }
+            }
+            changed = true;
+        }
+
+        // Kept even when empty: shared evidence relies on it to tell which
+        // outcomes this list already has.
+        self.inherited.insert(source_id.to_string(), current);
+        if changed {
+            self.replay();
+            self.replay_glicko();
+        }
+        changed
+    }
+
+    /// Adds (`delta` 1) or takes back (`delta` -1) an event recorded in
+    /// another list. Returns `false` when this list lacks either item, or has
+    /// no such event to take back. Bradley-Terry abilities are refit when the
+    /// list is loaded, so only Glicko-2 ratings are updated here.
+    fn apply_outcome(&mut self, event: &MatchEvent, delta: i64) -> bool {
+        let position = |id: &str| self.item_ids.iter().position(|item_id| item_id == id);
+        let (Some(i), Some(j)) = (position(&event.winner), position(&event.loser)) else {
+            return false;
+        };
+        if i == j || !self.matches_items(&self.item_ids) || !self.has_tie_matrix() {
+            return false;
+        }
+        if delta > 0 {
+            self.events.push(event.clone());
+        } else if !self.take_back(event) {
+            return false;
+        }
+
+        let score = if event.tie {
+            add_count(&mut self.tie_matrix[i][j], delta);
+            add_count(&mut self.tie_matrix[j][i], delta);
+            0.5
+        } else {
+            add_count(&mut self.win_matrix[i][j], delta);
+            1.0
+        };
+        for index in [i, j] {
+            if let Some(total) = self.match_totals.get_mut(index) {
+                add_count(total, delta);
+            }
+        }
+
+        if delta > 0 && self.glicko.len() == self.item_ids.len() {
+            let mut system = Glicko2::from_ratings(std::mem::take(&mut self.glicko));
+            system.record_game(i, j, score);
+            self.glicko = system.ratings().to_vec();
+        } else {
+            self.replay_glicko();
+        }
+        true
+    }
+
+    /// Retires items that the engine's intervals place outside the top K.
+    pub fn refresh_eliminations(&mut self, engine: &dyn RatingEngine) {
+        if let Some(top_k) = self.top_k.as_mut() {
+            top_k.update_eliminations(&engine.rating_intervals());
+        }
+    }
+
+    fn has_tie_matrix(&self) -> bool {
+        self.tie_matrix.len() == self.item_ids.len()
+            && self
+                .tie_matrix
+                .iter()
+                .all(|row| row.len() == self.item_ids.len())
+    }
+}
+
+#[derive(Debug)]
+pub enum StateError {
+    Malformed(String),
+    /// Written by a newer version of the app.
+    TooNew(u32),
+    Migration {
+        from: u32,
+        message: String,
+    },
+}
+
+impl fmt::Display for StateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StateError::Malformed(message) => write!(f, "unreadable data: {}", message),
+            StateError::TooNew(version) => write!(
+                f,
+                "saved by a newer version of the app (format {}, this one reads up to {})",
+                version, STATE_VERSION
+            ),
+            StateError::Migration { from, message } => {
+                write!(f, "could not upgrade from format {}: {}", from, message)
+            }
         }
     }
 }
 
-pub fn load_state() -> StoredAppState {
-    match LocalStorage::get::<StoredAppState>(STORAGE_KEY) {
-        Ok(state) => state,
+#[derive(Serialize)]
+struct StateEnvelope<'a> {
+    version: u32,
+    state: &'a StoredAppState,
+}
+
+pub fn encode_state(state: &StoredAppState) -> Result<String, serde_json::Error> {
+    serde_json::to_string(&StateEnvelope {
+        version: STATE_VERSION,
+        state,
+    })
+}
+
+/// Reads saved state of any known version, running the migrations it is
+/// missing in order.
+pub fn decode_state(text: &str) -> Result<StoredAppState, StateError> {
+    let value: Value =
+        serde_json::from_str(text).map_err(|err| StateError::Malformed(err.to_string()))?;
+    decode_value(value)
+}
+
+/// [`decode_state`] for JSON that is already parsed; backups share the
+/// envelope.
+pub(crate) fn decode_value(value: Value) -> Result<StoredAppState, StateError> {
+    let malformed = |err: serde_json::Error| StateError::Malformed(err.to_string());
+    let (mut version, mut state) = match value {
+        Value::Object(mut envelope)
+            if envelope.contains_key("version") && envelope.contains_key("state") =>
+        {
+            let version = envelope
+                .get("version")
+                .and_then(Value::as_u64)
+                .and_then(|version| u32::try_from(version).ok())
+                .ok_or_else(|| StateError::Malformed("the version is not a number".to_string()))?;
+            (version, envelope.remove("state").unwrap_or_default())
+        }
+        bare => (0, bare),
+    };
+    if version > STATE_VERSION {
+        return Err(StateError::TooNew(version));
+    }
+    while version < STATE_VERSION {
+        state = MIGRATIONS[version as usize](state).map_err(|message| StateError::Migration {
+            from: version,
+            message,
+        })?;
+        version += 1;
+    }
+    let mut state: StoredAppState = serde_json::from_value(state).map_err(malformed)?;
+    for list in state.lists.values_mut() {
+        list.replay();
+    }
+    Ok(state)
+}
+
+/// 0 -> 1: lists saved before ties were recorded have no `tie_matrix`, and
+/// the oldest have no `match_totals`; both are filled in from the wins.
+fn backfill_ties_and_totals(mut state: Value) -> Result<Value, String> {
+    if !state.is_object() {
+        return Err("the saved state is not an object".to_string());
+    }
+    let Some(lists) = state.get_mut("lists") else {
+        return Ok(state);
+    };
+    let lists = lists
+        .as_object_mut()
+        .ok_or_else(|| "`lists` is not an object".to_string())?;
+    for (id, list) in lists.iter_mut() {
+        let list = list
+            .as_object_mut()
+            .ok_or_else(|| format!("list '{}' is not an object", id))?;
+        let matrix = |list: &serde_json::Map<String, Value>, key: &str| {
+            serde_json::from_value::<Vec<Vec<u32>>>(list.get(key).cloned().unwrap_or_default())
+                .map_err(|err| format!("list '{}' has a broken {}: {}", id, key, err))
+        };
+        let wins = matrix(list, "win_matrix")?;
+        let count = wins.len();
+        if !list.contains_key("tie_matrix") {
+            list.insert(
+                "tie_matrix".to_string(),
+                Value::from(vec![vec![0u32; count]; count]),
+            );
+        }
+        let ties = matrix(list, "tie_matrix")?;
+        let has_totals = list
+            .get("match_totals")
+            .and_then(Value::as_array)
+            .is_some_and(|totals| totals.len() == count);
+        if !has_totals {
+            list.insert(
+                "match_totals".to_string(),
+                Value::from(compute_match_totals(&wins, &ties)),
+            );
+        }
+    }
+    Ok(state)
+}
+
+/// The parts of a version 1 list that [`events_from_counts`] reads.
+#[derive(Deserialize)]
+struct LegacyCounts {
+    #[serde(default)]
+    item_ids: Vec<String>,
+    #[serde(default)]
+    win_matrix: Vec<Vec<u32>>,
+    #[serde(default)]
+    tie_matrix: Vec<Vec<u32>>,
+    #[serde(default)]
+    inherited: HashMap<String, InheritedResults>,
+}
+
+/// 1 -> 2: lists kept only counts; each count becomes that many events with
+/// no time. Counts copied in from another list are attributed to it, as far
+/// as the list still has them; the rest become the list's own decisions.
+fn events_from_counts(mut state: Value) -> Result<Value, String> {
+    let Some(lists) = state.get_mut("lists").and_then(Value::as_object_mut) else {
+        return Ok(state);
+    };
+    for (id, list) in lists.iter_mut() {
+        let counts: LegacyCounts = serde_json::from_value(list.clone())
+            .map_err(|err| format!("list '{}' is broken: {}", id, err))?;
+        let count = counts.item_ids.len();
+        let square = |matrix: &[Vec<u32>]| {
+            matrix.len() == count && matrix.iter().all(|row| row.len() == count)
+        };
+        let mut wins = counts.win_matrix;
+        let mut ties = counts.tie_matrix;
+        if !square(&wins) {
+            wins = vec![vec![0; count]; count];
+        }
+        if !square(&ties) {
+            ties = vec![vec![0; count]; count];
+        }
+        let position = |id: &str| counts.item_ids.iter().position(|item_id| item_id == id);
+
+        let mut events = Vec::new();
+        let mut sources: Vec<(&String, &InheritedResults)> = counts.inherited.iter().collect();
+        sources.sort_by(|a, b| a.0.cmp(b.0));
+        for (source, copied) in sources {
+            let outcomes = copied
+                .wins
+                .iter()
+                .map(|outcome| (false, outcome))
+                .chain(copied.ties.iter().map(|outcome| (true, outcome)));
+            for (tie, (a, b, copies)) in outcomes {
+                let (Some(i), Some(j)) = (position(a), position(b)) else {
+                    continue;
+                };
+                let cell = if tie { ties[i][j] } else { wins[i][j] };
+                let taken = cell.min(*copies);
+                if tie {
+                    ties[i][j] -= taken;
+                    ties[j][i] = ties[j][i].saturating_sub(taken);
+                } else {
+                    wins[i][j] -= taken;
+                }
+                let event = MatchEvent {
+                    source: Some(source.clone()),
+                    ..MatchEvent::new(a, b, (!tie).then_some(a.as_str()))
+                };
+                events.extend(std::iter::repeat_n(event, taken as usize));
+            }
+        }
+        for (i, a) in counts.item_ids.iter().enumerate() {
+            for (j, b) in counts.item_ids.iter().enumerate() {
+                let own = MatchEvent::new(a, b, Some(a));
+                events.extend(std::iter::repeat_n(own, wins[i][j] as usize));
+                if j > i {
+                    let tie = MatchEvent::new(a, b, None);
+                    events.extend(std::iter::repeat_n(tie, ties[i][j] as usize));
+                }
+            }
+        }
+
+        let list = list
+            .as_object_mut()
+            .ok_or_else(|| format!("list '{}' is not an object", id))?;
+        for key in ["win_matrix", "tie_matrix", "match_totals"] {
+            list.remove(key);
+        }
+        let events = serde_json::to_value(events).map_err(|err| err.to_string())?;
+        list.insert("events".to_string(), events);
+    }
+    Ok(state)
+}
+
+/// Loads the saved state, with a message for the user when it could not be
+/// read. The unreadable text is then moved to a backup key first, so saving
+/// the fresh state does not destroy it.
+#[cfg(feature = "web")]
+pub fn load_state() -> (StoredAppState, Option<String>) {
+    let storage = LocalStorage::raw();
+    let text = match storage.get_item(STORAGE_KEY) {
+        Ok(Some(text)) => text,
+        Ok(None) => return (StoredAppState::default(), None),
+        Err(err) => {
+            warn!("Could not read saved state: {:?}", err);
+            return (StoredAppState::default(), None);
+        }
+    };
+    match decode_state(&text) {
+        Ok(state) => (state, None),
         Err(err) => {
             warn!("Falling back to default app state: {}", err);
-            StoredAppState::default()
+            let backup_key = format!("{}_{}", BACKUP_KEY_PREFIX, js_sys::Date::now() as u64);
+            // Removing first frees the room the copy needs.
+            let _ = storage.remove_item(STORAGE_KEY);
+            let notice = match storage.set_item(&backup_key, &text) {
+                Ok(()) => format!(
+                    "Your saved rankings could not be loaded ({}). They were kept in this browser's local storage under \"{}\".",
+                    err, backup_key
+                ),
+                Err(backup_err) => {
+                    warn!("Could not back up saved state: {:?}", backup_err);
+                    let _ = storage.set_item(STORAGE_KEY, &text);
+                    format!("Your saved rankings could not be loaded ({}).", err)
+                }
+            };
+            (StoredAppState::default(), Some(notice))
         }
     }
 }
 
+#[cfg(feature = "web")]
 pub fn save_state(state: &StoredAppState) {
-    if let Err(err) = LocalStorage::set(STORAGE_KEY, state) {
+    let result = encode_state(state)
+        .map_err(|err| err.to_string())
+        .and_then(|text| {
+            LocalStorage::raw()
+                .set_item(STORAGE_KEY, &text)
+                .map_err(|err| format!("{:?}", err))
+        });
+    if let Err(err) = result {
         warn!("Failed to persist state: {}", err);
     }
 }
@@ -90,11 +931,153 @@ pub fn upsert_list_state(app_state: &mut StoredAppState, list_id: &str, state: S
     app_state.lists.insert(list_id.to_string(), state);
 }
 
-pub fn align_list_state(existing: Option<StoredListState>, item_ids: &[String]) -> StoredListState {
+pub fn upsert_custom_list(app_state: &mut StoredAppState, list: CustomList) {
+    match app_state
+        .custom_lists
+        .iter_mut()
+        .find(|existing| existing.id == list.id)
+    {
+        Some(existing) => *existing = list,
+        None => app_state.custom_lists.push(list),
+    }
+}
+
+/// Drops a custom list together with its saved progress.
+pub fn remove_custom_list(app_state: &mut StoredAppState, list_id: &str) {
+    app_state.custom_lists.retain(|list| list.id != list_id);
+    app_state.lists.remove(list_id);
+    if app_state.selected_list.as_deref() == Some(list_id) {
+        app_state.selected_list = None;
+    }
+}
+
+pub fn cache_list(app_state: &mut StoredAppState, list: CachedList) {
+    match app_state
+        .cached_lists
+        .iter_mut()
+        .find(|existing| existing.id == list.id)
+    {
+        Some(existing) => *existing = list,
+        None => app_state.cached_lists.push(list),
+    }
+}
+
+/// Forgets a list opened from a URL together with its saved progress.
+pub fn remove_cached_list(app_state: &mut StoredAppState, list_id: &str) {
+    app_state.cached_lists.retain(|list| list.id != list_id);
+    app_state.lists.remove(list_id);
+    if app_state.selected_list.as_deref() == Some(list_id) {
+        app_state.selected_list = None;
+    }
+}
+
+/// Shared evidence: copies `event`, just recorded in `list_id` whose state is
+/// `origin`, to every other saved list that has both items. Composite lists
+/// and their sources note the outcome as already copied, so it is not
+/// inherited a second time. Returns how many lists were updated.
+pub fn share_result(
+    app_state: &mut StoredAppState,
+    list_id: &str,
+    origin: &mut StoredListState,
+    event: &MatchEvent,
+) -> usize {
+    let targets = share_outcome(app_state, list_id, event, 1);
+    for target in &targets {
+        if let Some(copied) = origin.inherited.get_mut(target) {
+            copied.adjust(&event.winner, &event.loser, event.winner_id(), 1);
+        }
+    }
+    targets.len()
+}
+
+/// Shares the decision `origin` has just recorded and marks it as shared, so
+/// that undoing or editing it later reaches the same lists whatever the
+/// shared-evidence setting is by then.
+pub fn share_latest_result(
+    app_state: &mut StoredAppState,
+    list_id: &str,
+    origin: &mut StoredListState,
+) -> usize {
+    let Some(event) = origin.events.last_mut() else {
+        return 0;
+    };
+    event.shared = true;
+    let event = event.clone();
+    share_result(app_state, list_id, origin, &event)
+}
+
+/// Passes an edit of `origin`'s history on to the other lists: removed events
+/// that were shared are taken back and added ones that were shared are shared
+/// again.
+pub fn share_history_edit(
+    app_state: &mut StoredAppState,
+    list_id: &str,
+    origin: &mut StoredListState,
+    edit: &HistoryEdit,
+) {
+    for event in edit.removed.iter().filter(|event| event.shared) {
+        unshare_result(app_state, list_id, origin, event);
+    }
+    for event in edit.added.iter().filter(|event| event.shared) {
+        share_result(app_state, list_id, origin, event);
+    }
+}
+
+/// Takes back what [`share_result`] applied, when `event` is undone or
+/// edited in `origin`.
+pub fn unshare_result(
+    app_state: &mut StoredAppState,
+    list_id: &str,
+    origin: &mut StoredListState,
+    event: &MatchEvent,
+) {
+    for target in share_outcome(app_state, list_id, event, -1) {
+        if let Some(copied) = origin.inherited.get_mut(&target) {
+            copied.adjust(&event.winner, &event.loser, event.winner_id(), -1);
+        }
+    }
+}
+
+fn share_outcome(
+    app_state: &mut StoredAppState,
+    list_id: &str,
+    event: &MatchEvent,
+    delta: i64,
+) -> Vec<String> {
+    let shared = MatchEvent {
+        source: Some(list_id.to_string()),
+        shared: false,
+        ..event.clone()
+    };
+    let mut targets = Vec::new();
+    for (target_id, target) in app_state.lists.iter_mut() {
+        if target_id == list_id || !target.apply_outcome(&shared, delta) {
+            continue;
+        }
+        if let Some(copied) = target.inherited.get_mut(list_id) {
+            copied.adjust(&event.winner, &event.loser, event.winner_id(), delta);
+        }
+        targets.push(target_id.clone());
+    }
+    targets
+}
+
+/// Fits saved progress onto the list's current items. `legacy_ids[k]` is the
+/// id item `k` had under the previous id scheme; pass an empty slice when there
+/// is none.
+pub fn align_list_state(
+    existing: Option<StoredListState>,
+    item_ids: &[String],
+    legacy_ids: &[String],
+) -> StoredListState {
+    let existing = existing.map(|state| migrate_legacy_ids(state, item_ids, legacy_ids));
     match existing {
         Some(mut state) if state.matches_items(item_ids) => {
-            if state.match_totals.len() != state.item_ids.len() {
-                state.match_totals = compute_match_totals(&state.win_matrix);
+            if !state.glicko.is_empty() && state.glicko.len() != state.item_ids.len() {
+                state.glicko.clear();
+            }
+            if !state.has_tie_matrix() || state.match_totals.len() != state.item_ids.len() {
+                state.replay();
             }
             state
         }
@@ -103,6 +1086,46 @@ pub fn align_list_state(existing: Option<StoredListState>, item_ids: &[String])
     }
 }
 
+/// Renames ids saved under the legacy scheme. A state counts as legacy when more
+/// of its ids are legacy ids than current ones, so a coincidental match between
+/// an old and a new id does not attach progress to the wrong item.
+fn migrate_legacy_ids(
+    mut state: StoredListState,
+    item_ids: &[String],
+    legacy_ids: &[String],
+) -> StoredListState {
+    if legacy_ids.len() != item_ids.len() || state.matches_items(item_ids) {
+        return state;
+    }
+
+    let current: HashSet<&String> = item_ids.iter().collect();
+    let renames: HashMap<&String, &String> = legacy_ids.iter().zip(item_ids).collect();
+    let current_hits = state
+        .item_ids
+        .iter()
+        .filter(|id| current.contains(id))
+        .count();
+    let legacy_hits = state
+        .item_ids
+        .iter()
+        .filter(|id| renames.contains_key(id))
+        .count();
+    if legacy_hits <= current_hits {
+        return state;
+    }
+
+    let events = state
+        .events
+        .iter_mut()
+        .flat_map(|event| [&mut event.winner, &mut event.loser]);
+    for id in state.item_ids.iter_mut().chain(events) {
+        if let Some(&renamed) = renames.get(id) {
+            *id = renamed.clone();
+        }
+    }
+    state
+}
+
 fn reorder_state(state: StoredListState, item_ids: &[String]) -> StoredListState {
     let n = item_ids.len();
     if n == 0 {
@@ -115,31 +1138,63 @@ fn reorder_state(state: StoredListState, item_ids: &[String]) -> StoredListState
     }
 
     let mut new_state = StoredListState::new(item_ids);
+    new_state.engine = state.engine;
+    new_state.mode = state.mode;
+    new_state.inherited = state.inherited.clone();
+    new_state.filter = state.filter.clone();
+    new_state.events = state.events.clone();
+    new_state.replay();
+    new_state.sort = state
+        .sort
+        .as_ref()
+        .map(|sort| sort.remap(&state.item_ids, item_ids));
+    new_state.top_k = state
+        .top_k
+        .as_ref()
+        .map(|top_k| top_k.remap(&state.item_ids, item_ids));
+    let remap_glicko = state.glicko.len() == state.item_ids.len() && !state.glicko.is_empty();
+    if remap_glicko {
+        new_state.glicko = vec![GlickoRating::default(); n];
+    }
 
     for (new_i, id_i) in item_ids.iter().enumerate() {
         if let Some(&old_i) = mapping.get(id_i) {
             if old_i < state.abilities.len() {
                 new_state.abilities[new_i] = state.abilities[old_i].max(1e-6);
             }
-            for (new_j, id_j) in item_ids.iter().enumerate() {
-                if let Some(&old_j) = mapping.get(id_j) {
-                    let value = state
-                        .win_matrix
-                        .get(old_i)
-                        .and_then(|row| row.get(old_j))
-                        .copied()
-                        .unwrap_or(0);
-                    new_state.win_matrix[new_i][new_j] = value;
-                }
+            if remap_glicko {
+                new_state.glicko[new_i] = state.glicko[old_i];
             }
         }
     }
-    new_state.match_totals = compute_match_totals(&new_state.win_matrix);
 
     new_state
 }
 
-fn compute_match_totals(win_matrix: &[Vec<u32>]) -> Vec<u32> {
+/// Per pair, how much `current` differs from `previous`.
+fn count_deltas(
+    previous: &[(String, String, u32)],
+    current: &[(String, String, u32)],
+) -> Vec<(String, String, i64)> {
+    let mut deltas: HashMap<(&String, &String), i64> = HashMap::new();
+    for (a, b, count) in previous {
+        *deltas.entry((a, b)).or_default() -= i64::from(*count);
+    }
+    for (a, b, count) in current {
+        *deltas.entry((a, b)).or_default() += i64::from(*count);
+    }
+    deltas
+        .into_iter()
+        .filter(|(_, delta)| *delta != 0)
+        .map(|((a, b), delta)| (a.clone(), b.clone(), delta))
+        .collect()
+}
+
+fn add_count(cell: &mut u32, delta: i64) {
+    *cell = (i64::from(*cell) + delta).clamp(0, i64::from(u32::MAX)) as u32;
+}
+
+fn compute_match_totals(win_matrix: &[Vec<u32>], tie_matrix: &[Vec<u32>]) -> Vec<u32> {
     let count = win_matrix.len();
     let mut totals = vec![0u32; count];
     for i in 0..count {
@@ -152,9 +1207,385 @@ fn compute_match_totals(win_matrix: &[Vec<u32>]) -> Vec<u32> {
                     .get(j)
                     .and_then(|row| row.get(i))
                     .copied()
+                    .unwrap_or(0)
+                + tie_matrix
+                    .get(i)
+                    .and_then(|row| row.get(j))
+                    .copied()
                     .unwrap_or(0);
             totals[i] = totals[i].saturating_add(matches);
         }
     }
     totals
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ranking::BradleyTerry;
+    use serde_json::json;
+
+    fn ids(values: &[&str]) -> Vec<String> {
+        values.iter().map(|value| value.to_string()).collect()
+    }
+
+    /// Logs `times` copies of one outcome and replays the list.
+    fn decide(
+        state: &mut StoredListState,
+        left: &str,
+        right: &str,
+        winner: Option<&str>,
+        times: usize,
+    ) {
+        let event = MatchEvent::new(left, right, winner);
+        state.events.extend(std::iter::repeat_n(event, times));
+        state.replay();
+    }
+
+    #[test]
+    fn legacy_ids_carry_progress_to_new_ids() {
+        let mut saved = StoredListState::new(&ids(&["iro", "ivo"]));
+        decide(&mut saved, "iro", "ivo", Some("iro"), 3);
+
+        let aligned = align_list_state(Some(saved), &ids(&["ivo", "ciro"]), &ids(&["ivo", "iro"]));
+
+        assert_eq!(aligned.item_ids, ids(&["ivo", "ciro"]));
+        assert_eq!(aligned.win_matrix[1][0], 3);
+        assert_eq!(aligned.match_totals, vec![3, 3]);
+    }
+
+    #[test]
+    fn inherited_results_are_only_added_once() {
+        let mut source = StoredListState::new(&ids(&["liam", "noah", "ivo"]));
+        decide(&mut source, "liam", "noah", Some("liam"), 2);
+        decide(&mut source, "ivo", "liam", Some("ivo"), 1);
+        decide(&mut source, "noah", "liam", None, 1);
+        let mut composite = StoredListState::new(&ids(&["noah", "liam", "oliver"]));
+        decide(&mut composite, "noah", "liam", Some("liam"), 1);
+
+        assert!(composite.inherit_results("us", &source));
+        assert!(!composite.inherit_results("us", &source));
+        assert_eq!(composite.win_matrix[1][0], 3);
+        assert_eq!(composite.tie_matrix[0][1], 1);
+        assert_eq!(composite.match_totals, vec![4, 4, 0]);
+        let copied = composite
+            .events
+            .iter()
+            .filter(|event| event.source.is_some());
+        assert_eq!(copied.count(), 3);
+
+        // Progress reset in the source leaves only the composite's own result.
+        let reset = StoredListState::new(&source.item_ids);
+        assert!(composite.inherit_results("us", &reset));
+        assert_eq!(composite.win_matrix[1][0], 1);
+        assert_eq!(composite.tie_matrix[1][0], 0);
+        assert_eq!(composite.match_totals, vec![1, 1, 0]);
+        assert_eq!(
+            composite.events,
+            [MatchEvent::new("liam", "noah", Some("liam"))]
+        );
+        assert_eq!(composite.inherited["us"], InheritedResults::default());
+    }
+
+    #[test]
+    fn shared_results_reach_lists_with_both_items() {
+        let mut app_state = StoredAppState::default();
+        app_state.lists.insert(
+            "uk".to_string(),
+            StoredListState::new(&ids(&["oliver", "noah", "george"])),
+        );
+        app_state.lists.insert(
+            "aus".to_string(),
+            StoredListState::new(&ids(&["oliver", "jack"])),
+        );
+        let mut all = StoredListState::new(&ids(&["noah", "oliver", "jack", "george"]));
+        all.inherited
+            .insert("us".to_string(), InheritedResults::default());
+        app_state.lists.insert("all".to_string(), all);
+
+        let mut us = StoredListState::new(&ids(&["noah", "oliver"]));
+        let mut engine = BradleyTerry::new(2);
+        assert!(us.record_result(0, 1, Some(0), &mut engine, &EventStamp::default()));
+        let event = us.events[0].clone();
+        let shared = share_result(&mut app_state, "us", &mut us, &event);
+
+        assert_eq!(shared, 2);
+        assert_eq!(app_state.lists["uk"].win_matrix[1][0], 1);
+        assert_eq!(
+            app_state.lists["uk"].events[0].source.as_deref(),
+            Some("us")
+        );
+        assert_eq!(app_state.lists["aus"].total_matches(), 0);
+        // The composite already has the outcome, so inheriting adds nothing.
+        let mut all = app_state.lists["all"].clone();
+        assert!(!all.inherit_results("us", &us));
+        assert_eq!(all.win_matrix[0][1], 1);
+
+        unshare_result(&mut app_state, "us", &mut us, &event);
+        assert_eq!(app_state.lists["uk"].win_matrix[1][0], 0);
+        assert!(app_state.lists["uk"].events.is_empty());
+        assert_eq!(app_state.lists["all"].match_totals, vec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn taking_back_a_shared_result_restores_glicko_ratings() {
+        let mut uk = StoredListState::new(&ids(&["oliver", "noah", "george"]));
+        uk.engine = RatingEngineKind::Glicko2;
+        let mut engine = uk.restore_engine();
+        let stamp = EventStamp::default();
+        for (left, right, winner) in [(0, 2, 0), (1, 2, 2), (0, 1, 1)] {
+            assert!(uk.record_result(left, right, Some(winner), engine.as_mut(), &stamp));
+        }
+        let before = uk.glicko.clone();
+        let mut app_state = StoredAppState::default();
+        app_state.lists.insert("uk".to_string(), uk);
+
+        let mut us = StoredListState::new(&ids(&["noah", "oliver"]));
+        let mut engine = BradleyTerry::new(2);
+        assert!(us.record_result(0, 1, Some(1), &mut engine, &stamp));
+        let event = us.events[0].clone();
+        share_result(&mut app_state, "us", &mut us, &event);
+        assert_ne!(app_state.lists["uk"].glicko, before);
+
+        unshare_result(&mut app_state, "us", &mut us, &event);
+        assert_eq!(app_state.lists["uk"].glicko, before);
+    }
+
+    #[test]
+    fn history_edits_follow_how_the_event_was_recorded() {
+        let mut app_state = StoredAppState::default();
+        app_state.lists.insert(
+            "uk".to_string(),
+            StoredListState::new(&ids(&["oliver", "noah"])),
+        );
+        let mut us = StoredListState::new(&ids(&["noah", "oliver"]));
+        let mut engine = BradleyTerry::new(2);
+        let stamp = EventStamp::default();
+
+        // Shared when made, then the setting is turned off before the undo.
+        app_state.shared_evidence = true;
+        assert!(us.record_result(0, 1, Some(0), &mut engine, &stamp));
+        assert_eq!(share_latest_result(&mut app_state, "us", &mut us), 1);
+        app_state.shared_evidence = false;
+        let edit = us.undo().unwrap();
+        share_history_edit(&mut app_state, "us", &mut us, &edit);
+        assert_eq!(app_state.lists["uk"].total_matches(), 0);
+
+        let edit = us.redo().unwrap();
+        share_history_edit(&mut app_state, "us", &mut us, &edit);
+        assert_eq!(app_state.lists["uk"].win_matrix[1][0], 1);
+        let edit = us.flip(0).unwrap();
+        share_history_edit(&mut app_state, "us", &mut us, &edit);
+        assert_eq!(app_state.lists["uk"].win_matrix[0][1], 1);
+        assert_eq!(app_state.lists["uk"].total_matches(), 1);
+        assert!(!app_state.lists["uk"].events[0].shared);
+
+        // Kept to this list when made, then the setting is turned on.
+        assert!(us.record_result(0, 1, Some(1), &mut engine, &stamp));
+        app_state.shared_evidence = true;
+        let edit = us.undo().unwrap();
+        share_history_edit(&mut app_state, "us", &mut us, &edit);
+        assert_eq!(app_state.lists["uk"].total_matches(), 1);
+        assert_eq!(app_state.lists["uk"].win_matrix[0][1], 1);
+    }
+
+    #[test]
+    fn current_ids_are_not_migrated() {
+        // "iro" is both a current id and another item's legacy id.
+        let mut saved = StoredListState::new(&ids(&["iro", "ciro"]));
+        decide(&mut saved, "iro", "ciro", Some("iro"), 2);
+
+        let aligned =
+            align_list_state(Some(saved), &ids(&["iro", "ciro"]), &ids(&["iro-2", "iro"]));
+
+        assert_eq!(aligned.item_ids, ids(&["iro", "ciro"]));
+        assert_eq!(aligned.win_matrix[0][1], 2);
+    }
+
+    #[test]
+    fn migration_0_backfills_ties_and_totals() {
+        let bare = json!({
+            "selected_list": "fruits",
+            "lists": {
+                "fruits": {
+                    "item_ids": ["a", "b", "c"],
+                    "win_matrix": [[0, 2, 0], [1, 0, 0], [0, 0, 0]],
+                    "abilities": [1.0, 1.0, 1.0]
+                }
+            }
+        });
+
+        let state = decode_state(&bare.to_string()).unwrap();
+        let list = &state.lists["fruits"];
+        assert_eq!(state.selected_list.as_deref(), Some("fruits"));
+        assert_eq!(list.tie_matrix, vec![vec![0; 3]; 3]);
+        assert_eq!(list.match_totals, [3, 3, 0]);
+        assert_eq!(list.total_matches(), 3);
+        assert_eq!(list.events.len(), 3);
+
+        let broken = json!({ "lists": { "fruits": { "win_matrix": "oops" } } });
+        assert!(matches!(
+            decode_state(&broken.to_string()),
+            Err(StateError::Migration { from: 0, .. })
+        ));
+        assert!(decode_state("[1, 2]").is_err());
+    }
+
+    #[test]
+    fn migration_1_turns_counts_into_events() {
+        let saved = json!({
+            "version": 1,
+            "state": {
+                "lists": {
+                    "all": {
+                        "item_ids": ["a", "b", "c"],
+                        "win_matrix": [[0, 3, 0], [0, 0, 0], [1, 0, 0]],
+                        "tie_matrix": [[0, 0, 0], [0, 0, 2], [0, 2, 0]],
+                        "match_totals": [4, 5, 3],
+                        "abilities": [1.0, 1.0, 1.0],
+                        "inherited": {
+                            "us": { "wins": [["a", "b", 2], ["a", "gone", 1]], "ties": [] },
+                            "uk": { "ties": [["b", "c", 1]] }
+                        }
+                    }
+                }
+            }
+        });
+
+        let state = decode_state(&saved.to_string()).unwrap();
+        let list = &state.lists["all"];
+        assert_eq!(list.win_matrix, [[0, 3, 0], [0, 0, 0], [1, 0, 0]]);
+        assert_eq!(list.tie_matrix[2][1], 2);
+        assert_eq!(list.match_totals, [4, 5, 3]);
+        let from = |source: Option<&str>| {
+            list.events
+                .iter()
+                .filter(|event| event.source.as_deref() == source)
+                .count()
+        };
+        assert_eq!((from(Some("us")), from(Some("uk")), from(None)), (2, 1, 3));
+        assert!(list.events.iter().all(|event| event.at == 0));
+    }
+
+    #[test]
+    fn recorded_results_match_their_replay() {
+        let mut list = StoredListState::new(&ids(&["a", "b", "c"]));
+        let mut engine = BradleyTerry::new(3);
+        let stamp = EventStamp {
+            at: 1_700_000_000_000,
+            session: "0badcafe".to_string(),
+        };
+        assert!(list.record_result(0, 1, Some(1), &mut engine, &stamp));
+        assert!(list.record_result(2, 0, None, &mut engine, &stamp));
+        assert!(!list.record_result(1, 1, Some(1), &mut engine, &stamp));
+
+        assert_eq!(list.events.len(), 2);
+        assert_eq!(list.events[0].winner, "b");
+        assert_eq!(list.events[0].strategy, Some(list.mode));
+        assert_eq!(list.events[1].session, "0badcafe");
+        let recorded = list.clone();
+        list.replay();
+        assert_eq!(list.win_matrix, recorded.win_matrix);
+        assert_eq!(list.tie_matrix, recorded.tie_matrix);
+        assert_eq!(list.match_totals, recorded.match_totals);
+    }
+
+    #[test]
+    fn history_edits_keep_later_decisions() {
+        let mut list = StoredListState::new(&ids(&["a", "b", "c"]));
+        list.set_mode(MatchupMode::ExactOrder);
+        let mut engine = list.restore_engine();
+        let stamp = EventStamp::default();
+        for (left, right, winner) in [(0, 1, 1), (1, 2, 2), (0, 2, 2)] {
+            assert!(list.record_result(left, right, Some(winner), engine.as_mut(), &stamp));
+        }
+        list.events.insert(
+            0,
+            MatchEvent {
+                source: Some("us".to_string()),
+                ..MatchEvent::new("a", "c", None)
+            },
+        );
+        list.replay();
+        let sorted = list.sort.clone();
+
+        let undone = list.undo().unwrap();
+        assert_eq!(undone.removed[0].winner, "c");
+        assert_eq!(list.redo.len(), 1);
+        assert_eq!(list.total_matches(), 3);
+        list.redo().unwrap();
+        assert_eq!(list.sort, sorted);
+        assert!(list.redo.is_empty());
+
+        assert!(list.flip(0).is_none());
+        let flipped = list.flip(1).unwrap();
+        assert_eq!(
+            (
+                flipped.removed[0].winner.as_str(),
+                flipped.added[0].winner.as_str()
+            ),
+            ("b", "a")
+        );
+        assert_eq!(list.win_matrix[0][1], 1);
+        list.revert(2).unwrap();
+        assert_eq!(list.events.len(), 3);
+        assert_eq!(list.win_matrix[1][2], 0);
+        assert_eq!(list.win_matrix[2][0], 1);
+
+        list.undo().unwrap();
+        assert!(list.record_result(0, 1, None, engine.as_mut(), &stamp));
+        assert!(list.redo.is_empty());
+    }
+
+    #[test]
+    fn undo_and_redo_retrace_glicko_ratings() {
+        let mut list = StoredListState::new(&ids(&["a", "b", "c"]));
+        list.engine = RatingEngineKind::Glicko2;
+        let mut engine = list.restore_engine();
+        let stamp = EventStamp::default();
+        let mut ratings = Vec::new();
+        for (left, right, winner) in [(0, 1, 0), (1, 2, 2), (0, 2, 2)] {
+            ratings.push(list.glicko.clone());
+            assert!(list.record_result(left, right, Some(winner), engine.as_mut(), &stamp));
+        }
+        let latest = list.glicko.clone();
+
+        let undone = list.undo().unwrap();
+        assert_eq!(list.glicko, ratings[2]);
+        assert_eq!(undone.engine.glicko_ratings(), Some(ratings[2].as_slice()));
+        list.redo().unwrap();
+        assert_eq!(list.glicko, latest);
+    }
+
+    #[test]
+    fn saved_state_round_trips_through_the_envelope() {
+        let mut state = StoredAppState {
+            selected_list: Some("fruits".to_string()),
+            shared_evidence: true,
+            ..StoredAppState::default()
+        };
+        let mut list = StoredListState::new(&ids(&["a", "b"]));
+        decide(&mut list, "a", "b", Some("b"), 4);
+        list.filter = "#red".to_string();
+        state.lists.insert("fruits".to_string(), list);
+
+        let text = encode_state(&state).unwrap();
+        assert!(text.starts_with(&format!("{{\"version\":{}", STATE_VERSION)));
+        let decoded = decode_state(&text).unwrap();
+        assert_eq!(decoded.lists["fruits"].win_matrix[1][0], 4);
+        assert_eq!(
+            serde_json::to_value(&decoded).unwrap(),
+            serde_json::to_value(&state).unwrap()
+        );
+
+        let newer = json!({ "version": STATE_VERSION + 1, "state": {} });
+        assert!(matches!(
+            decode_state(&newer.to_string()),
+            Err(StateError::TooNew(_))
+        ));
+        assert!(matches!(
+            decode_state("{ not json"),
+            Err(StateError::Malformed(_))
+        ));
+    }
+}
@@ -35,8 +35,7 @@ if not exist "%LISTS_DIR%" (
     exit /b 0
 )
 
-powershell -NoLogo -NoProfile -Command ^
-    "$files = Get-ChildItem -Path '%LISTS_DIR%' -Filter '*.json' -ErrorAction SilentlyContinue | Select-Object -ExpandProperty BaseName | Sort-Object; $array = @($files); $json = ConvertTo-Json -InputObject $array -Compress; Set-Content -Path 'assets/index.json' -Value $json -Encoding UTF8"
+powershell -NoLogo -NoProfile -ExecutionPolicy Bypass -File "%ROOT_DIR%generate_index.ps1" -ListsDir "%LISTS_DIR%" -IndexPath "assets\index.json"
 if errorlevel 1 (
     echo Failed to generate assets index.
 )